@@ -0,0 +1,36 @@
+use engine::client::Client;
+use anyhow::Result;
+use clap::Parser;
+use log::error;
+
+fn main() {
+    env_logger::init();
+    let args = Args::parse();
+
+    if let Err(err) = client(&args) {
+        error!("Error happened: {}", err);
+    }
+}
+
+fn client(args: &Args) -> Result<()> {
+    let client = Client::new(&args.destination);
+    let balance = client.balance(&args.address)?;
+    println!("Amount: {}\nUTxOs: {}\nLocked: {}", balance.amount, balance.utxos, balance.locked);
+    for (asset, amount) in &balance.assets {
+        println!("Asset {}: {}", asset, amount);
+    }
+    for (asset, amount) in &balance.locked_assets {
+        println!("Locked asset {}: {}", asset, amount);
+    }
+    Ok(())
+}
+
+#[derive(Parser)]
+#[command(version, about)]
+struct Args {
+    #[arg(short, long, default_value("0.0.0.0:9065"))]
+    destination: String,
+
+    #[arg(short, long)]
+    address: String,
+}
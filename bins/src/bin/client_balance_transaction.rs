@@ -1,5 +1,7 @@
-use engine::client::Client;
-use anyhow::Result;
+use std::{path::{PathBuf, Path}, fs, str::FromStr};
+
+use engine::{client::Client, blockchain::{coin_selection::CoinSelection, utxo::TimeLock}};
+use anyhow::{Result, anyhow};
 use clap::Parser;
 use log::error;
 
@@ -15,23 +17,86 @@ fn main() {
 
 fn client(args: &Args) -> Result<()> {
     let client = Client::new(&args.destination);
-    let balanced_transaction_response = client.balance_transaction(&args.from_address, &args.to_address, args.amount)?;
+    let from: Vec<&str> = args.from_address.iter().map(String::as_str).collect();
+
+    let recipients = match (&args.recipients, &args.to_address, args.amount) {
+        (Some(path), _, _) => read_recipients(path)?,
+        (None, Some(to_address), Some(amount)) => vec![(to_address.to_owned(), amount)],
+        _ => return Err(anyhow!("Either --recipients or both --to-address and --amount are required")),
+    };
+    let to: Vec<(&str, u64)> = recipients.iter().map(|(address, amount)| (address.as_str(), *amount)).collect();
+
+    let balanced_transaction_response = client.balance_transaction(&from, &to, args.fee_rate, CoinSelection::from_str(&args.coin_selection)?, TimeLock::from_parts(args.lock_height, args.lock_timestamp)?, args.asset.as_deref(), args.issue.as_deref())?;
     println!("{}", serde_json::to_string_pretty(&balanced_transaction_response)?);
     Ok(())
 }
 
+/**
+ * Reads `address,amount` lines, empty lines and lines starting with # are skipped.
+ * The first line is treated as a header if its amount is not a number.
+ */
+fn read_recipients(path: &Path) -> Result<Vec<(String, u64)>> {
+    let csv = fs::read_to_string(path).map_err(|err| anyhow!("Unable to read {}: {}", path.display(), err))?;
+
+    let mut recipients = Vec::new();
+    for (idx, line) in csv.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let (address, amount) = line.split_once(',').ok_or(anyhow!("Line {} must be in address,amount format", idx + 1))?;
+        match amount.trim().parse::<u64>() {
+            Ok(amount) => recipients.push((address.trim().to_owned(), amount)),
+            Err(_) if idx == 0 => continue,
+            Err(err) => return Err(anyhow!("Amount on line {} is wrong: {}", idx + 1, err)),
+        }
+    }
+
+    Ok(recipients)
+}
+
 #[derive(Parser)]
 #[command(version, about)]
 struct Args {
     #[arg(short, long, default_value("0.0.0.0:9065"))]
     destination: String,
 
-    #[arg(short, long)]
-    from_address: String,
+    /// Address funds are taken from, repeat to take funds from several addresses
+    #[arg(short, long, required(true))]
+    from_address: Vec<String>,
 
-    #[arg(short, long)]
-    to_address: String,
+    #[arg(short, long, conflicts_with("recipients"))]
+    to_address: Option<String>,
+
+    #[arg(short, long, conflicts_with("recipients"))]
+    amount: Option<u64>,
 
+    /// CSV file with address,amount lines, one transaction pays all of them
     #[arg(short, long)]
-    amount: u64,
+    recipients: Option<PathBuf>,
+
+    /// Fee per input and output of the transaction, paid to the validator
+    #[arg(long, default_value("0"))]
+    fee_rate: u64,
+
+    /// Strategy choosing the inputs: largest-first, smallest-first, oldest-first or branch-and-bound
+    #[arg(long, default_value("largest-first"))]
+    coin_selection: String,
+
+    /// Recipients can't spend the funds before the block at this height
+    #[arg(long, conflicts_with("lock_timestamp"))]
+    lock_height: Option<u64>,
+
+    /// Recipients can't spend the funds before a block with this timestamp, milliseconds since unix epoch
+    #[arg(long)]
+    lock_timestamp: Option<u64>,
+
+    /// Id of the asset paid to the recipients, the native coin if not given
+    #[arg(long, conflicts_with("issue"))]
+    asset: Option<String>,
+
+    /// Name of a new asset issued by the first sender, the recipients get the whole issued amount
+    #[arg(long)]
+    issue: Option<String>,
 }
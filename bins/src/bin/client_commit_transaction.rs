@@ -1,4 +1,6 @@
-use engine::client::Client;
+use std::path::PathBuf;
+
+use engine::{client::Client, blockchain::{multisig::Multisig, script::Script, signed_balanced_transaction::Witness}};
 use anyhow::Result;
 use clap::Parser;
 use log::error;
@@ -15,7 +17,19 @@ fn main() {
 
 fn client(args: &Args) -> Result<()> {
     let client = Client::new(&args.destination);
-    println!("{:?}", client.commit_transaction(&args.cbor, &args.private_key)?);
+    let private_keys: Vec<&str> = args.private_key.iter().map(String::as_str).collect();
+    let witness = Witness {
+        multisigs: args.multisig.iter().map(|path| Multisig::load(path)).collect::<Result<Vec<_>>>()?,
+        scripts: args.script.iter().map(|path| Script::load(path)).collect::<Result<Vec<_>>>()?,
+        preimages: args.preimage.clone(),
+    };
+    let response = if args.cbor.len() == 1 {
+        client.commit_transaction(&args.cbor[0], &private_keys, &witness)?
+    } else {
+        let cbors: Vec<&str> = args.cbor.iter().map(String::as_str).collect();
+        client.commit_transactions(&cbors, &private_keys, &witness)?
+    };
+    println!("{:?}", response);
     Ok(())
 }
 
@@ -25,9 +39,23 @@ struct Args {
     #[arg(short, long, default_value("0.0.0.0:9065"))]
     destination: String,
 
+    /// Balanced transaction, all transactions are committed in a single block if repeated
+    #[arg(short, long, required(true))]
+    cbor: Vec<String>,
+
+    /// Private key of an owner of the inputs, repeat if inputs belong to several owners
+    #[arg(short, long, required(true))]
+    private_key: Vec<String>,
+
+    /// JSON file with the policy of a multisig address the inputs belong to, its keys are given by --private-key
     #[arg(short, long)]
-    cbor: String,
+    multisig: Vec<PathBuf>,
 
+    /// JSON file with the script of a script address the inputs belong to
     #[arg(short, long)]
-    private_key: String,
+    script: Vec<PathBuf>,
+
+    /// Hex encoded preimage of a hash checked by a script
+    #[arg(long)]
+    preimage: Vec<String>,
 }
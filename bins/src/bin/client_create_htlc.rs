@@ -0,0 +1,79 @@
+use std::{fs, path::PathBuf};
+
+use engine::{client::Client, blockchain::{htlc::Htlc, utxo::TimeLock}, model::PublicKeyStr};
+use anyhow::{Result, anyhow};
+use clap::Parser;
+use log::error;
+
+
+fn main() {
+    env_logger::init();
+    let args = Args::parse();
+
+    if let Err(err) = client(&args) {
+        error!("Error happened: {}", err);
+    }
+}
+
+fn client(args: &Args) -> Result<()> {
+    let client = Client::new(&args.destination);
+    let hash = match (&args.hash, &args.preimage) {
+        (Some(hash), None) => hash.to_owned(),
+        (None, Some(preimage)) => Htlc::hash_of(preimage)?,
+        _ => return Err(anyhow!("Either --hash or --preimage is required")),
+    };
+    let deadline = TimeLock::from_parts(args.deadline_height, args.deadline_timestamp)?
+        .ok_or(anyhow!("Either --deadline-height or --deadline-timestamp is required"))?;
+
+    let htlc = Htlc::new(&PublicKeyStr::from_str(&args.from_address), &PublicKeyStr::from_str(&args.to_address), &hash, deadline)?;
+    fs::write(&args.output, serde_json::to_string_pretty(&htlc)?)?;
+    println!("Contract address: {}", htlc.address().0.0);
+    println!("{:?}", client.create_htlc(&htlc, args.amount, args.fee_rate, &args.private_key)?);
+    Ok(())
+}
+
+#[derive(Parser)]
+#[command(version, about)]
+struct Args {
+    #[arg(short, long, default_value("0.0.0.0:9065"))]
+    destination: String,
+
+    /// Sender, the funds are refunded to it after the deadline
+    #[arg(short, long)]
+    from_address: String,
+
+    /// Private key of the sender
+    #[arg(short, long)]
+    private_key: String,
+
+    /// Recipient, it claims the funds with the preimage before the deadline
+    #[arg(short, long)]
+    to_address: String,
+
+    #[arg(short, long)]
+    amount: u64,
+
+    /// Hex encoded SHA-256 hash of the secret, when the secret is known to the recipient only
+    #[arg(long, conflicts_with("preimage"))]
+    hash: Option<String>,
+
+    /// Hex encoded secret, only its hash is put into the contract
+    #[arg(long)]
+    preimage: Option<String>,
+
+    /// Recipient can't claim the funds from the block at this height on
+    #[arg(long, conflicts_with("deadline_timestamp"))]
+    deadline_height: Option<u64>,
+
+    /// Recipient can't claim the funds in blocks from this timestamp on, milliseconds since unix epoch
+    #[arg(long)]
+    deadline_timestamp: Option<u64>,
+
+    /// Fee per input and output of the transaction, paid to the validator
+    #[arg(long, default_value("0"))]
+    fee_rate: u64,
+
+    /// File the contract is written to, it's needed to claim or refund the funds
+    #[arg(short, long)]
+    output: PathBuf,
+}
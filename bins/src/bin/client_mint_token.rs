@@ -0,0 +1,49 @@
+use engine::client::Client;
+use anyhow::Result;
+use clap::Parser;
+use log::error;
+
+
+fn main() {
+    env_logger::init();
+    let args = Args::parse();
+
+    if let Err(err) = client(&args) {
+        error!("Error happened: {}", err);
+    }
+}
+
+fn client(args: &Args) -> Result<()> {
+    let client = Client::new(&args.destination);
+    let (token_id, response) = client.mint_token(&args.from_address, &args.to_address, &args.metadata, args.fee_rate, &args.private_key)?;
+    println!("Token id: {}", token_id);
+    println!("{:?}", response);
+    Ok(())
+}
+
+#[derive(Parser)]
+#[command(version, about)]
+struct Args {
+    #[arg(short, long, default_value("0.0.0.0:9065"))]
+    destination: String,
+
+    /// Minter, it pays the fee and its first spent output determines the token id
+    #[arg(short, long)]
+    from_address: String,
+
+    /// Private key of the minter
+    #[arg(short, long)]
+    private_key: String,
+
+    /// Owner of the minted token
+    #[arg(short, long)]
+    to_address: String,
+
+    /// Hex encoded bytes describing the token, at most 256 bytes
+    #[arg(short, long, default_value(""))]
+    metadata: String,
+
+    /// Fee per input and output of the transaction, paid to the validator
+    #[arg(long, default_value("0"))]
+    fee_rate: u64,
+}
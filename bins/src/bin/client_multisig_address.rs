@@ -0,0 +1,43 @@
+use std::{fs, path::PathBuf};
+
+use engine::{blockchain::multisig::Multisig, model::PublicKeyStr};
+use anyhow::Result;
+use clap::Parser;
+use log::error;
+
+
+fn main() {
+    env_logger::init();
+    let args = Args::parse();
+
+    if let Err(err) = client(&args) {
+        error!("Error happened: {}", err);
+    }
+}
+
+fn client(args: &Args) -> Result<()> {
+    let keys: Vec<PublicKeyStr> = args.public_key.iter().map(|key| PublicKeyStr::from_str(key)).collect();
+    let multisig = Multisig::new(args.threshold, &keys)?;
+    let policy = serde_json::to_string_pretty(&multisig)?;
+    if let Some(output) = &args.output {
+        fs::write(output, &policy)?;
+    }
+    println!("Address: {}\nPolicy: {}", multisig.address().0.0, policy);
+    Ok(())
+}
+
+#[derive(Parser)]
+#[command(version, about)]
+struct Args {
+    /// Number of signatures needed to spend from the address
+    #[arg(short, long)]
+    threshold: u32,
+
+    /// Key of the multisig, repeat for every key; order of the keys changes the address
+    #[arg(short, long, required(true))]
+    public_key: Vec<String>,
+
+    /// File the policy is written to, pass it to client_commit_transaction to spend from the address
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+}
@@ -0,0 +1,42 @@
+use std::path::PathBuf;
+
+use engine::{client::Client, blockchain::htlc::Htlc};
+use anyhow::Result;
+use clap::Parser;
+use log::error;
+
+
+fn main() {
+    env_logger::init();
+    let args = Args::parse();
+
+    if let Err(err) = client(&args) {
+        error!("Error happened: {}", err);
+    }
+}
+
+fn client(args: &Args) -> Result<()> {
+    let client = Client::new(&args.destination);
+    let htlc = Htlc::load(&args.contract)?;
+    println!("{:?}", client.refund_htlc(&htlc, args.fee_rate, &args.private_key)?);
+    Ok(())
+}
+
+#[derive(Parser)]
+#[command(version, about)]
+struct Args {
+    #[arg(short, long, default_value("0.0.0.0:9065"))]
+    destination: String,
+
+    /// Contract file written by client_create_htlc
+    #[arg(short, long)]
+    contract: PathBuf,
+
+    /// Private key of the sender
+    #[arg(short, long)]
+    private_key: String,
+
+    /// Fee per input and output of the transaction, paid to the validator
+    #[arg(long, default_value("0"))]
+    fee_rate: u64,
+}
@@ -0,0 +1,30 @@
+use std::path::PathBuf;
+
+use engine::blockchain::script::Script;
+use anyhow::Result;
+use clap::Parser;
+use log::error;
+
+
+fn main() {
+    env_logger::init();
+    let args = Args::parse();
+
+    if let Err(err) = client(&args) {
+        error!("Error happened: {}", err);
+    }
+}
+
+fn client(args: &Args) -> Result<()> {
+    let script = Script::load(&args.script)?;
+    println!("Address: {}", script.address().0.0);
+    Ok(())
+}
+
+#[derive(Parser)]
+#[command(version, about)]
+struct Args {
+    /// JSON file with the script, pass it to client_commit_transaction to spend from the address
+    #[arg(short, long)]
+    script: PathBuf,
+}
@@ -0,0 +1,45 @@
+use engine::client::Client;
+use anyhow::Result;
+use clap::Parser;
+use log::error;
+
+
+fn main() {
+    env_logger::init();
+    let args = Args::parse();
+
+    if let Err(err) = client(&args) {
+        error!("Error happened: {}", err);
+    }
+}
+
+fn client(args: &Args) -> Result<()> {
+    let client = Client::new(&args.destination);
+    println!("{:?}", client.transfer_token(&args.from_address, &args.to_address, &args.token_id, args.fee_rate, &args.private_key)?);
+    Ok(())
+}
+
+#[derive(Parser)]
+#[command(version, about)]
+struct Args {
+    #[arg(short, long, default_value("0.0.0.0:9065"))]
+    destination: String,
+
+    /// Owner of the token, it also pays the fee
+    #[arg(short, long)]
+    from_address: String,
+
+    /// Private key of the owner
+    #[arg(short, long)]
+    private_key: String,
+
+    #[arg(short, long)]
+    to_address: String,
+
+    #[arg(long)]
+    token_id: String,
+
+    /// Fee per input and output of the transaction, paid to the validator
+    #[arg(long, default_value("0"))]
+    fee_rate: u64,
+}
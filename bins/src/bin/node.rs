@@ -1,3 +1,5 @@
+use std::path::PathBuf;
+
 use engine::run_node;
 use clap::Parser;
 use log::error;
@@ -8,7 +10,7 @@ async fn main() {
     env_logger::init();
     let args = Args::parse();
 
-    if let Err(err) = run_node(args.host, args.port, args.remote_validator.as_deref(), &args.private_key, &args.public_key).await {
+    if let Err(err) = run_node(args.host, args.port, args.remote_validator.as_deref(), &args.private_key, &args.public_key, &args.genesis, args.data_dir.as_deref(), args.min_fee_rate).await {
         error!("Error happened: {}", err)
     }
 
@@ -31,5 +33,17 @@ struct Args {
     private_key: String,
 
     #[arg(long)]
-    public_key: String
+    public_key: String,
+
+    /// JSON file with the chain id, initial allocations and validators of the network
+    #[arg(long)]
+    genesis: PathBuf,
+
+    /// Directory where blocks are persisted, blockchain is kept in memory only if not set
+    #[arg(long, default_value(None))]
+    data_dir: Option<PathBuf>,
+
+    /// Minimum fee per input and output, transactions paying less are not committed
+    #[arg(long, default_value("0"))]
+    min_fee_rate: u64,
 }
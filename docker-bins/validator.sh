@@ -2,8 +2,8 @@
 
 if [ -z ${REMOTE_VALIDATOR} ];
 then
-    node --host $VALIDATOR_CALLBACK --port 9065 --private-key $(</test-data/$VALIDATOR_SK) --public-key $(</test-data/$VALIDATOR_PK)
+    node --host $VALIDATOR_CALLBACK --port 9065 --private-key $(</test-data/$VALIDATOR_SK) --public-key $(</test-data/$VALIDATOR_PK) --genesis /test-data/genesis.json
 else
-    node --host $VALIDATOR_CALLBACK --port 9065 --private-key $(</test-data/$VALIDATOR_SK) --public-key $(</test-data/$VALIDATOR_PK) --remote-validator $REMOTE_VALIDATOR:9065
+    node --host $VALIDATOR_CALLBACK --port 9065 --private-key $(</test-data/$VALIDATOR_SK) --public-key $(</test-data/$VALIDATOR_PK) --genesis /test-data/genesis.json --remote-validator $REMOTE_VALIDATOR:9065
 fi
 
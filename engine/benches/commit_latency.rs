@@ -0,0 +1,48 @@
+use std::time::{Duration, Instant};
+
+use engine::{blockchain::{blockchain::BlockChain, transaction::Transaction, coin_selection::CoinSelection, genesis::{Genesis, GenesisAllocation}}, encryption::generate_rsa_keypair_custom};
+use rsa::RsaPrivateKey;
+
+/**
+ * Measures how long it takes to commit a transaction depending on the length of the blockchain.
+ * Run with `cargo bench -p engine`, commit latency is expected to stay flat as the blockchain grows.
+ */
+fn main() {
+    let checkpoints = [0, 500, 1000, 2000, 4000];
+    let samples = 100;
+
+    let (validator_private_key, validator_public_key) = generate_rsa_keypair_custom().unwrap();
+    let (priv_1, pub_1) = generate_rsa_keypair_custom().unwrap();
+    let (priv_2, pub_2) = generate_rsa_keypair_custom().unwrap();
+    let rsa_priv_1 = RsaPrivateKey::try_from(&priv_1).unwrap();
+    let rsa_priv_2 = RsaPrivateKey::try_from(&priv_2).unwrap();
+
+    let mut blockchain = BlockChain::new(&validator_public_key, Genesis::new("bench", vec![GenesisAllocation::new(&pub_1, 1_000_000)], Vec::new()));
+
+    // coins are sent back and forth between two wallets, so that every transaction has a change output
+    let commit_next = |blockchain: &mut BlockChain| -> Duration {
+        let (from, to, private_key) = match blockchain.len() % 2 {
+            0 => (&pub_1, &pub_2, &rsa_priv_1),
+            _ => (&pub_2, &pub_1, &rsa_priv_2),
+        };
+        let transaction = Transaction::new(from, to, 1)
+            .balance_transaction(blockchain, 0, CoinSelection::default())
+            .unwrap()
+            .sign(private_key)
+            .unwrap();
+
+        let started = Instant::now();
+        blockchain.commit_transaction(&transaction, &validator_private_key).unwrap();
+        started.elapsed()
+    };
+
+    println!("{:>12} {:>12} {:>12}", "blocks", "utxos", "commit, µs");
+    for checkpoint in checkpoints {
+        while blockchain.len() < checkpoint {
+            commit_next(&mut blockchain);
+        }
+
+        let total: Duration = (0..samples).map(|_| commit_next(&mut blockchain)).sum();
+        println!("{:>12} {:>12} {:>12}", checkpoint, blockchain.utxos().len(), (total / samples).as_micros());
+    }
+}
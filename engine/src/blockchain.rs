@@ -1,11 +1,36 @@
+#[allow(clippy::module_inception)]
 pub mod blockchain;
 pub mod signed_balanced_transaction;
 pub mod utxo;
+pub mod utxo_set;
 pub mod uuid;
 mod transaction_test;
+mod blockchain_test;
+mod merkle_test;
+mod block_store_test;
+mod utxo_set_test;
+mod genesis_test;
+mod coin_selection_test;
+mod encoding_test;
+mod multisig_test;
+mod script_test;
+mod htlc_test;
+mod asset_test;
+mod token_test;
 pub mod transaction;
+pub mod coin_selection;
 pub mod balanced_transaction;
-mod transaction_id;
+pub mod transaction_id;
+pub mod multisig;
+pub mod script;
+pub mod htlc;
+pub mod asset;
+pub mod token;
 pub mod cbor;
+pub mod encoding;
 pub mod block;
+pub mod merkle;
+pub mod genesis;
+pub mod block_store;
+pub mod file_block_store;
 pub mod validator_signature;
\ No newline at end of file
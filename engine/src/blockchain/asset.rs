@@ -0,0 +1,67 @@
+use std::fmt::{Display, Formatter};
+
+use anyhow::{Result, anyhow};
+use serde::{Serialize, Deserialize};
+use sha1::Digest;
+use sha2::Sha256;
+
+use crate::model::PublicKeyStr;
+
+use super::encoding::asset_id_bytes;
+
+const MAX_ASSET_NAME_BYTES: usize = 64;
+
+/**
+ * Hex encoded hash of the issuer and the name of an asset, outputs without an asset id carry the native coin
+ */
+#[derive(Clone, Eq, Hash, PartialEq, PartialOrd, Ord, Debug, Serialize, Deserialize)]
+pub struct AssetId(pub String);
+
+/**
+ * Mints a new asset, the issuing transaction pays the whole amount to its outputs of the asset.
+ * Every issuer can issue an asset of a given name only once.
+ */
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Issuance {
+    /**
+     * Key the asset is issued under, it must sign the issuing transaction
+     */
+    pub issuer: PublicKeyStr,
+    pub name: String,
+    pub amount: u64,
+}
+
+impl AssetId {
+    pub fn new(id: &str) -> AssetId {
+        AssetId(id.to_lowercase())
+    }
+}
+
+impl Display for AssetId {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Issuance {
+    pub fn new(issuer: &PublicKeyStr, name: &str, amount: u64) -> Issuance {
+        Issuance { issuer: issuer.clone(), name: name.to_owned(), amount }
+    }
+
+    /**
+     * Id of the issued asset, it doesn't depend on the amount
+     */
+    pub fn asset_id(&self) -> AssetId {
+        AssetId(hex::encode(Sha256::digest(asset_id_bytes(&self.issuer, &self.name))))
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        if self.name.is_empty() || self.name.len() > MAX_ASSET_NAME_BYTES {
+            return Err(anyhow!("Asset name must have between 1 and {} bytes, {} given", MAX_ASSET_NAME_BYTES, self.name.len()));
+        }
+        if self.amount == 0 {
+            return Err(anyhow!("Issued amount of asset {} must be positive", self.name));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,45 @@
+#[cfg(test)]
+mod tests {
+    use crate::{blockchain::{transaction::Transaction, coin_selection::CoinSelection, blockchain::BlockChain, genesis::{Genesis, GenesisAllocation}, asset::Issuance}, encryption::generate_rsa_keypair_custom};
+
+    #[test]
+    fn assets_are_issued_and_balanced_separately() {
+        let (validator_private_key, validator_public_key) = &generate_rsa_keypair_custom().unwrap();
+        let (priv_1, pub_1) = &generate_rsa_keypair_custom().unwrap();
+        let (priv_2, pub_2) = &generate_rsa_keypair_custom().unwrap();
+        let mut blockchain = BlockChain::new(validator_public_key, Genesis::new("test", vec![GenesisAllocation::new(pub_1, 10)], Vec::new()));
+        let gold = Issuance::new(pub_1, "gold", 100).asset_id();
+
+        // the issuer pays the fee in the native coin
+        let issue = Transaction::new(pub_1, pub_2, 100).with_issuance(Some("gold".to_owned()));
+        issue.balance_transaction(&blockchain, 1, CoinSelection::default()).unwrap()
+            .sign(&priv_1.try_into().unwrap()).unwrap()
+            .commit(&mut blockchain, validator_private_key).unwrap();
+        assert_eq!(blockchain.balance(pub_1).unwrap().amount, 7, "Fee must be paid in the native coin");
+        assert_eq!(blockchain.balance(pub_2).unwrap().assets.get(&gold), Some(&100), "Issued amount must go to the recipient");
+        assert_eq!(blockchain.balance(pub_2).unwrap().amount, 0, "Recipient must get no native coin");
+
+        let err = issue.balance_transaction(&blockchain, 0, CoinSelection::default()).unwrap()
+            .sign(&priv_1.try_into().unwrap()).unwrap()
+            .commit(&mut blockchain, validator_private_key).unwrap_err();
+        assert!(err.to_string().contains("is already issued"), "Error is wrong: {}", err);
+
+        let transfer = Transaction::new(pub_2, pub_1, 30).with_asset(Some(gold.clone()));
+        let mut unbalanced = transfer.balance_transaction(&blockchain, 0, CoinSelection::default()).unwrap();
+        unbalanced.outputs.last_mut().unwrap().amount = 31;
+        let err = unbalanced.sign(&priv_2.try_into().unwrap()).unwrap().commit(&mut blockchain, validator_private_key).unwrap_err();
+        assert!(err.to_string().contains("differ from its inputs"), "Error is wrong: {}", err);
+
+        transfer.balance_transaction(&blockchain, 0, CoinSelection::default()).unwrap()
+            .sign(&priv_2.try_into().unwrap()).unwrap()
+            .commit(&mut blockchain, validator_private_key).unwrap();
+        assert_eq!(blockchain.balance(pub_1).unwrap().assets.get(&gold), Some(&30), "Balance of the recipient is wrong");
+        assert_eq!(blockchain.balance(pub_2).unwrap().assets.get(&gold), Some(&70), "Change must go back to the sender");
+        assert_eq!(blockchain.balance(pub_1).unwrap().amount, 7, "Native balance must not change");
+
+        let err = Transaction::new(pub_1, pub_2, 31).with_asset(Some(gold))
+            .balance_transaction(&blockchain, 0, CoinSelection::default()).unwrap_err();
+        assert!(err.to_string().starts_with("Not enough of asset"), "Error is wrong: {}", err);
+        assert_eq!(blockchain.audit(), Ok(()));
+    }
+}
@@ -1,32 +1,114 @@
+use std::collections::HashSet;
+
 use rsa::RsaPrivateKey;
 use serde::{Serialize, Deserialize};
-use anyhow::Result;
-use crate::model::Signature;
+use anyhow::{Result, anyhow};
+use crate::model::{Signature, PublicKeyStr};
 
-use super::{utxo::UnspentOutput, transaction_id::TransactionId, signed_balanced_transaction::SignedBalancedTransaction, cbor::Cbor};
+use super::{asset::{AssetId, Issuance}, multisig::Multisig, script::Script, token::TokenMint, utxo::{OutPoint, TransactionOutput}, transaction_id::TransactionId, signed_balanced_transaction::{SignedBalancedTransaction, InputSignature, Witness}, cbor::Cbor, encoding::CanonicalEncode};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct BalancedTransaction {
-    pub id: TransactionId,
-    pub inputs: Vec<UnspentOutput>,
-    pub outputs: Vec<UnspentOutput>,
+    // the transaction can only be committed to the chain it was created for
+    pub chain_id: String,
+    // outputs spent by the transaction, their owners and amounts are looked up from the chain
+    pub inputs: Vec<OutPoint>,
+    pub outputs: Vec<TransactionOutput>,
+    // height of the block a reward belongs to, it makes ids of rewards unique; not set for other transactions
+    #[serde(default)]
+    pub reward_height: Option<u64>,
+    // new asset minted by the transaction
+    #[serde(default)]
+    pub issuance: Option<Issuance>,
+    // unique token minted by the transaction
+    #[serde(default)]
+    pub mint: Option<TokenMint>,
 }
 
 impl BalancedTransaction {
+    /**
+     * Id of the transaction is the hash of its body, signatures are not part of it.
+     * So the id is known before the transaction is signed and re-encoding signatures can't change it.
+     */
+    pub fn id(&self) -> TransactionId {
+        TransactionId(hex::encode(self.canonical_hash()))
+    }
+
+    /**
+     * Id of the token minted by the transaction, if it mints one and has an input to derive the id from
+     */
+    pub fn minted_token(&self) -> Option<AssetId> {
+        self.mint.as_ref().and(self.inputs.first()).map(TokenMint::token_id)
+    }
+
+    /**
+     * Number of inputs and outputs, fee rates are per unit of weight
+     */
+    pub fn weight(&self) -> u64 {
+        (self.inputs.len() + self.outputs.len()) as u64
+    }
+
     pub fn to_cbor(&self) -> Result<Vec<u8>> {
         Ok(serde_cbor::to_vec(self)?)
     }
 
     pub fn sign(&self, private_key: &RsaPrivateKey) -> Result<SignedBalancedTransaction> {
-        let cbor = self.to_cbor()?;
+        self.sign_with(std::slice::from_ref(private_key))
+    }
+
+    /**
+     * Signs the transaction with every given key, inputs only reference outputs so their owners are not known here.
+     * Keys of exactly the owners of the inputs, or keys of their multisigs and scripts, must be given, otherwise validators reject the transaction.
+     */
+    pub fn sign_with(&self, private_keys: &[RsaPrivateKey]) -> Result<SignedBalancedTransaction> {
+        if private_keys.is_empty() {
+            return Err(anyhow!("No private key provided to sign the transaction"));
+        }
+        let bytes = self.canonical_bytes();
 
-        let signature = Signature::sign(&private_key, &cbor)?;
+        let mut seen = HashSet::new();
+        let mut signatures = Vec::new();
+        for private_key in private_keys {
+            let address = PublicKeyStr::try_from(&private_key.to_public_key())?;
+            if seen.insert(address.clone()) {
+                signatures.push(InputSignature { address, signature: Signature::sign(private_key, &bytes)? });
+            }
+        }
 
         Ok(SignedBalancedTransaction {
             balanced_transaction: self.clone(),
-            signature,
+            signatures,
+            witness: Witness::default(),
         })
     }
+
+    /**
+     * Signs the transaction with those of the given keys which own its inputs, issue it or belong to a policy it spends from, and reveals only the policies owning its inputs.
+     * Owners are the addresses of the spent outputs, looked up from the chain.
+     * So a single set of keys and witness can sign every transaction of a batch spending funds of different owners.
+     */
+    pub fn sign_by_owners(&self, owners: &[PublicKeyStr], private_keys: &[RsaPrivateKey], witness: &Witness) -> Result<SignedBalancedTransaction> {
+        let multisigs: Vec<Multisig> = witness.multisigs.iter().filter(|multisig| owners.contains(&multisig.address())).cloned().collect();
+        let scripts: Vec<Script> = witness.scripts.iter().filter(|script| owners.contains(&script.address())).cloned().collect();
+        let issuer = self.issuance.as_ref().map(|issuance| &issuance.issuer);
+
+        let mut signing_keys = Vec::new();
+        for private_key in private_keys {
+            let address = PublicKeyStr::try_from(&private_key.to_public_key())?;
+            let is_signer = owners.contains(&address) || issuer == Some(&address)
+                || multisigs.iter().any(|multisig| multisig.keys.contains(&address))
+                || scripts.iter().any(|script| script.keys().contains(&&address));
+            if is_signer {
+                signing_keys.push(private_key.clone());
+            }
+        }
+        if signing_keys.is_empty() {
+            return Err(anyhow!("None of the given private keys can sign the transaction"));
+        }
+
+        let preimages = if scripts.is_empty() { Vec::new() } else { witness.preimages.clone() };
+        Ok(self.sign_with(&signing_keys)?.with_witness(Witness { multisigs, scripts, preimages }))
+    }
 }
 
 impl TryFrom<&Cbor> for BalancedTransaction {
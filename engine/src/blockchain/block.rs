@@ -1,66 +1,194 @@
 use protocol::request::ValidatorWithSignature;
 use rsa::RsaPrivateKey;
-use serde::Serialize;
-use sha1::Digest;
-use sha2::Sha256;
-use anyhow::Result;
+use serde::{Serialize, Deserialize};
+use anyhow::{Result, anyhow};
 use crate::model::{Signature, PublicKeyStr, PrivateKeyStr};
 
-use super::{signed_balanced_transaction::SignedBalancedTransaction, cbor::Cbor, validator_signature::ValidatorSignature};
+use super::{signed_balanced_transaction::SignedBalancedTransaction, validator_signature::ValidatorSignature, merkle::merkle_root, cbor::Cbor, encoding::CanonicalEncode};
 
-#[derive(Clone, Serialize)]
+/**
+ * Part of the block the block hash is computed over
+ */
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct BlockHeader {
+    /**
+     * The genesis is at height 0, so the first block has height 1
+     */
+    pub height: u64,
+    /**
+     * Hash of the previous block, or hash of the genesis for the first block
+     */
+    pub prev_hash: String,
+    /**
+     * Milliseconds since unix epoch, set by the proposer
+     */
+    pub timestamp: u64,
+    /**
+     * Validator which created the block
+     */
+    pub proposer: PublicKeyStr,
+    /**
+     * Merkle root of the witness hashes of all transactions in the block, in the order they are applied
+     */
+    pub transactions_root: String,
+}
+
+impl BlockHeader {
+    /**
+     * Hash of the canonical encoding of the header
+     */
+    pub fn hash(&self) -> Vec<u8> {
+        self.canonical_hash()
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Block {
     /**
-     * Hash of this block computation is based on hash of the previous block on blockchain plus hash of the transaction
+     * Hash of the block header
      */
     pub hash: String,
-    pub transaction: SignedBalancedTransaction,
+    pub header: BlockHeader,
+    pub transactions: Vec<SignedBalancedTransaction>,
 
     /**
-     * Entire block with block hash and transaction hash is signed by validator's private key
-     * To resolve contention between validators. 
+     * Block hash is signed by validator's private key
+     * To resolve contention between validators.
      */
     validator_signatures: Vec<ValidatorSignature>
 }
 
 impl Block {
-    pub fn create_block_and_sign(previous_block_hash: &[u8], transaction: &SignedBalancedTransaction, validator_private_key: &PrivateKeyStr) -> Result<Block> {
-        let mut hasher = Sha256::new();
-        hasher.update(previous_block_hash);
-        hasher.update(transaction.hash()?);
-        let next_block_hash = hex::encode(hasher.finalize().to_vec());
+    pub fn new(height: u64, previous_block_hash: &str, timestamp: u64, proposer: &PublicKeyStr, transactions: &[SignedBalancedTransaction]) -> Result<Block> {
+        let header = BlockHeader {
+            height,
+            prev_hash: previous_block_hash.to_owned(),
+            timestamp,
+            proposer: proposer.clone(),
+            transactions_root: hex::encode(Self::compute_merkle_root(transactions)?),
+        };
 
+        Ok(Block {
+            hash: hex::encode(header.hash()),
+            header,
+            transactions: transactions.to_vec(),
+            validator_signatures: Vec::new(),
+        })
+    }
+
+    /**
+     * Creates a block proposed by the owner of the given key, the block is signed by the proposer
+     */
+    pub fn create_block_and_sign(height: u64, previous_block_hash: &str, timestamp: u64, transactions: &[SignedBalancedTransaction], validator_private_key: &PrivateKeyStr) -> Result<Block> {
+        let private_key = RsaPrivateKey::try_from(validator_private_key)?;
+        let proposer = PublicKeyStr::try_from(&private_key.to_public_key())?;
+
+        let mut block = Self::new(height, previous_block_hash, timestamp, &proposer, transactions)?;
+        block.sign(validator_private_key)?;
+
+        Ok(block)
+    }
+
+    /**
+     * Adds signature of the owner of the given key to the block and returns it
+     */
+    pub fn sign(&mut self, validator_private_key: &PrivateKeyStr) -> Result<ValidatorSignature> {
         let private_key = RsaPrivateKey::try_from(validator_private_key)?;
-        let transaction_cbor = hex::decode(Cbor::try_from(transaction)?.0)?;
-        let validator_signature = Signature::sign(&private_key, &transaction_cbor)?;
+        let signature = Signature::sign(&private_key, &hex::decode(&self.hash)?)?;
         let public_key = PublicKeyStr::try_from(&private_key.to_public_key())?;
 
-        Ok(Block {
-            hash: next_block_hash, 
-            transaction: transaction.clone(), 
-            validator_signatures: vec![ValidatorSignature::new(&public_key, &validator_signature)]
-        })
+        let validator_signature = ValidatorSignature::new(&public_key, &signature);
+        self.add_validator_signature(validator_signature.clone());
+        Ok(validator_signature)
+    }
+
+    /**
+     * Makes sure the block follows the given one and its hash matches the content
+     */
+    pub fn verify_block(&self, height: u64, previous_block_hash: &str) -> Result<()> {
+        if self.header.height != height {
+            return Err(anyhow!("block height {} does not match expected height {}", self.header.height, height));
+        }
+        if self.header.prev_hash != previous_block_hash {
+            return Err(anyhow!("previous hash {} does not match hash of the previous block {}", self.header.prev_hash, previous_block_hash));
+        }
+
+        let merkle_root = Self::compute_merkle_root(&self.transactions)?;
+        if hex::encode(merkle_root) != self.header.transactions_root {
+            return Err(anyhow!("transactions root {} does not match transactions of the block", self.header.transactions_root));
+        }
+
+        if hex::encode(self.header.hash()) != self.hash {
+            return Err(anyhow!("block hash {} does not match its header", self.hash));
+        }
+
+        Ok(())
     }
 
-    pub fn verify_block(&self, previous_block_hash: &[u8]) -> Result<bool> {
-        let mut hasher = Sha256::new();
-        hasher.update(previous_block_hash);
-        hasher.update(self.transaction.hash()?);
-        let computed_hash = hasher.finalize().to_vec();
+    /**
+     * Makes sure every signature of the block is valid and the proposer is one of the signers
+     */
+    pub fn verify_signatures(&self) -> Result<()> {
+        for signature in &self.validator_signatures {
+            signature.verify(&self.hash)?;
+        }
+        if self.signature_of(&self.header.proposer).is_none() {
+            return Err(anyhow!("block {} is not signed by its proposer {}", self.hash, self.header.proposer));
+        }
+        Ok(())
+    }
 
-        Ok(computed_hash == hex::decode(&self.hash)?)
+    fn compute_merkle_root(transactions: &[SignedBalancedTransaction]) -> Result<Vec<u8>> {
+        let hashes: Vec<Vec<u8>> = transactions.iter().map(|transaction| transaction.witness_hash()).collect();
+        merkle_root(&hashes)
     }
 
-    pub fn add_validator_signature(&mut self, signature: ValidatorSignature) {
-        if let Some(_) = self.validator_signatures.iter().find(|_signature| **_signature == signature) {
-            return;
+    /**
+     * Returns false if the signature is already present in the block
+     */
+    pub fn add_validator_signature(&mut self, signature: ValidatorSignature) -> bool {
+        if self.validator_signatures.contains(&signature) {
+            return false;
         }
         self.validator_signatures.push(signature);
+        true
+    }
+
+    /**
+     * Splits transactions into the reward of the proposer, if there is one, and the rest of them
+     */
+    pub fn split_reward(&self) -> (Option<&SignedBalancedTransaction>, &[SignedBalancedTransaction]) {
+        match self.transactions.split_first() {
+            Some((first, rest)) if first.is_reward() => (Some(first), rest),
+            _ => (None, &self.transactions),
+        }
     }
 
     pub fn validator_signatures(&self) -> &[ValidatorSignature] {
         &self.validator_signatures
     }
+
+    pub fn signature_of(&self, validator_public_key: &PublicKeyStr) -> Option<&ValidatorSignature> {
+        self.validator_signatures.iter().find(|signature| signature.validator_public_key == *validator_public_key)
+    }
+}
+
+impl TryFrom<&Cbor> for Block {
+    type Error = anyhow::Error;
+
+    fn try_from(value: &Cbor) -> Result<Self, Self::Error> {
+        let cbor_bytes = hex::decode(&value.0)?;
+        Ok(serde_cbor::from_slice(&cbor_bytes)?)
+    }
+}
+
+impl TryFrom<&Block> for Cbor {
+    type Error = anyhow::Error;
+
+    fn try_from(value: &Block) -> Result<Self, Self::Error> {
+        let cbor = serde_cbor::to_vec(value)?;
+        Ok(Cbor(hex::encode(&cbor)))
+    }
 }
 
 impl From<&ValidatorWithSignature> for ValidatorSignature {
@@ -70,4 +198,4 @@ impl From<&ValidatorWithSignature> for ValidatorSignature {
             &Signature::from_string(&v.signature)
         )
     }
-}
\ No newline at end of file
+}
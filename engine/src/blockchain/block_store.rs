@@ -0,0 +1,68 @@
+use anyhow::{Result, anyhow};
+
+use super::{block::Block, validator_signature::ValidatorSignature};
+
+/**
+ * Storage of the blocks of a blockchain.
+ * Blocks are addressed by their index on the blockchain, the first block after the initial utxo has index 0.
+ */
+pub trait BlockStore: Send {
+    fn len(&self) -> usize;
+
+    fn get(&self, index: usize) -> Option<&Block>;
+
+    fn append(&mut self, block: Block) -> Result<()>;
+
+    /**
+     * Returns false if the signature is already present in the block
+     */
+    fn add_validator_signature(&mut self, index: usize, signature: ValidatorSignature) -> Result<bool>;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn last(&self) -> Option<&Block> {
+        self.len().checked_sub(1).and_then(|index| self.get(index))
+    }
+}
+
+/**
+ * Keeps blocks in memory only, blockchain is lost once the node is stopped
+ */
+#[derive(Default)]
+pub struct InMemoryBlockStore {
+    blocks: Vec<Block>,
+}
+
+impl InMemoryBlockStore {
+    pub fn new() -> Self {
+        InMemoryBlockStore { blocks: Vec::new() }
+    }
+}
+
+impl From<Vec<Block>> for InMemoryBlockStore {
+    fn from(blocks: Vec<Block>) -> Self {
+        InMemoryBlockStore { blocks }
+    }
+}
+
+impl BlockStore for InMemoryBlockStore {
+    fn len(&self) -> usize {
+        self.blocks.len()
+    }
+
+    fn get(&self, index: usize) -> Option<&Block> {
+        self.blocks.get(index)
+    }
+
+    fn append(&mut self, block: Block) -> Result<()> {
+        self.blocks.push(block);
+        Ok(())
+    }
+
+    fn add_validator_signature(&mut self, index: usize, signature: ValidatorSignature) -> Result<bool> {
+        let block = self.blocks.get_mut(index).ok_or(anyhow!("Block with index {} not found", index))?;
+        Ok(block.add_validator_signature(signature))
+    }
+}
@@ -0,0 +1,106 @@
+#[cfg(test)]
+mod tests {
+    use std::{fs::{self, OpenOptions}, io::Write};
+
+    use crate::{blockchain::{transaction::Transaction, coin_selection::CoinSelection, blockchain::BlockChain, genesis::{Genesis, GenesisAllocation}, uuid::Uuid, block_store::{BlockStore, InMemoryBlockStore}, file_block_store::FileBlockStore}, encryption::generate_rsa_keypair_custom};
+
+    #[test]
+    fn blockchain_works_with_every_store() {
+        let data_dir = std::env::temp_dir().join(format!("blockchain-test-{}", Uuid::generate().0));
+        let stores: Vec<Box<dyn BlockStore>> = vec![
+            Box::new(InMemoryBlockStore::new()),
+            Box::new(FileBlockStore::open(&data_dir).unwrap()),
+        ];
+
+        let (validator_private_key, validator_public_key) = &generate_rsa_keypair_custom().unwrap();
+        let (priv_1, pub_1) = &generate_rsa_keypair_custom().unwrap();
+        let (_, pub_2) = &generate_rsa_keypair_custom().unwrap();
+        let genesis = Genesis::new("test", vec![GenesisAllocation::new(pub_1, 10)], Vec::new());
+
+        for store in stores {
+            let mut blockchain = BlockChain::with_store(validator_public_key, genesis.clone(), store).unwrap();
+            assert!(blockchain.is_empty(), "New blockchain must be empty");
+
+            let block = Transaction::new(pub_1, pub_2, 10)
+                .balance_transaction(&blockchain, 0, CoinSelection::default())
+                .unwrap()
+                .sign(&priv_1.try_into().unwrap())
+                .unwrap()
+                .commit(&mut blockchain, validator_private_key)
+                .unwrap();
+
+            assert_eq!(blockchain.len(), 1, "Number of blocks is wrong");
+            assert_eq!(blockchain.last_block().unwrap().hash, block.hash, "Last block is wrong");
+            assert_eq!(blockchain.blockchain_hash(), block.hash, "Blockchain hash is wrong");
+            assert!(Transaction::new(pub_1, pub_2, 1).balance_transaction(&blockchain, 0, CoinSelection::default()).is_err(), "Funds must be spent already");
+        }
+
+        fs::remove_dir_all(&data_dir).unwrap();
+    }
+
+    #[test]
+    fn blockchain_is_restored_from_data_dir() {
+        let data_dir = std::env::temp_dir().join(format!("blockchain-test-{}", Uuid::generate().0));
+        let (validator_private_key, validator_public_key) = &generate_rsa_keypair_custom().unwrap();
+        let (priv_1, pub_1) = &generate_rsa_keypair_custom().unwrap();
+        let (priv_2, pub_2) = &generate_rsa_keypair_custom().unwrap();
+        let genesis = Genesis::new("test", vec![GenesisAllocation::new(pub_1, 10)], Vec::new());
+
+        let mut blockchain = BlockChain::open(validator_public_key, genesis.clone(), &data_dir).unwrap();
+        for amount in [3, 4] {
+            Transaction::new(pub_1, pub_2, amount)
+                .balance_transaction(&blockchain, 0, CoinSelection::default())
+                .unwrap()
+                .sign(&priv_1.try_into().unwrap())
+                .unwrap()
+                .commit(&mut blockchain, validator_private_key)
+                .unwrap();
+        }
+        let first_block_hash = blockchain.block_at_height(1).unwrap().hash.to_owned();
+        let extra_signature = blockchain.block_at_height(1).unwrap().clone().sign(priv_2).unwrap();
+        blockchain.add_validator_signature(&first_block_hash, extra_signature.clone()).unwrap();
+        let blockchain_hash = blockchain.blockchain_hash();
+        drop(blockchain);
+
+        // simulate a record which was only partially written
+        let mut log_file = OpenOptions::new().append(true).open(data_dir.join("blocks.log")).unwrap();
+        log_file.write_all(&100_u64.to_be_bytes()).unwrap();
+        log_file.write_all(&[1, 2, 3]).unwrap();
+        drop(log_file);
+
+        let blockchain = BlockChain::open(validator_public_key, genesis.clone(), &data_dir).unwrap();
+        assert_eq!(blockchain.len(), 2, "Number of restored blocks is wrong");
+        assert_eq!(blockchain.blockchain_hash(), blockchain_hash, "Restored blockchain hash is wrong");
+        assert_eq!(blockchain.block_at_height(1).unwrap().validator_signatures().len(), 2, "Number of restored signatures is wrong");
+        assert!(blockchain.block_at_height(1).unwrap().validator_signatures().contains(&extra_signature), "Extra signature was not restored");
+
+        // blocks restored from the disk can't be spent twice
+        let transaction = Transaction::new(pub_1, pub_2, 3).balance_transaction(&blockchain, 0, CoinSelection::default()).unwrap();
+        assert_eq!(blockchain.utxos().get(&transaction.inputs[0]).unwrap().amount, 3, "Restored utxos are wrong");
+
+        fs::remove_dir_all(&data_dir).unwrap();
+    }
+
+    #[test]
+    fn restoring_fails_for_a_different_genesis() {
+        let data_dir = std::env::temp_dir().join(format!("blockchain-test-{}", Uuid::generate().0));
+        let (validator_private_key, validator_public_key) = &generate_rsa_keypair_custom().unwrap();
+        let (priv_1, pub_1) = &generate_rsa_keypair_custom().unwrap();
+
+        let genesis = Genesis::new("test", vec![GenesisAllocation::new(pub_1, 10)], Vec::new());
+        let mut blockchain = BlockChain::open(validator_public_key, genesis.clone(), &data_dir).unwrap();
+        Transaction::new(pub_1, pub_1, 5)
+            .balance_transaction(&blockchain, 0, CoinSelection::default())
+            .unwrap()
+            .sign(&priv_1.try_into().unwrap())
+            .unwrap()
+            .commit(&mut blockchain, validator_private_key)
+            .unwrap();
+        drop(blockchain);
+
+        let other_genesis = Genesis { chain_id: "other".to_owned(), ..genesis };
+        assert!(BlockChain::open(validator_public_key, other_genesis, &data_dir).is_err(), "Blocks of another blockchain must be rejected");
+
+        fs::remove_dir_all(&data_dir).unwrap();
+    }
+}
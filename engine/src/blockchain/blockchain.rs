@@ -1,125 +1,528 @@
 
-use std::collections::HashSet;
+use std::{path::Path, fmt::{Display, Formatter}, collections::{HashSet, BTreeMap}};
 
-use crate::model::{PublicKeyStr, PrivateKeyStr};
+use crate::{model::{PublicKeyStr, PrivateKeyStr}, utils::now_millis};
 
-use super::{utxo::UnspentOutput, signed_balanced_transaction::{SignedBalancedTransaction}, block::Block};
+use super::{asset::AssetId, utxo::{OutPoint, BlockTime}, transaction_id::TransactionId, genesis::Genesis, signed_balanced_transaction::{SignedBalancedTransaction, Witness, total_amount}, block::{Block, BlockHeader}, validator_signature::ValidatorSignature, block_store::{BlockStore, InMemoryBlockStore}, file_block_store::FileBlockStore, utxo_set::{UtxoSet, Balance, Supply}};
 use anyhow::{Result, anyhow};
-use rsa::RsaPublicKey;
+use rsa::RsaPrivateKey;
 
 pub struct BlockChain {
-    pub initial_utxo: UnspentOutput,
-    pub blocks: Vec<Block>,
+    pub genesis: Genesis,
+    store: Box<dyn BlockStore>,
+    utxos: UtxoSet,
+    // hash of the last verified block, or of the genesis if there are no blocks yet
+    tip: String,
 }
 
+// how far ahead of the local clock timestamp of a received block can be
+const MAX_CLOCK_DRIFT_MILLIS: u64 = 60_000;
+
+/**
+ * Describes the first block which didn't pass the audit
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuditFailure {
+    pub height: u64,
+    pub reason: String,
+}
+
+impl Display for AuditFailure {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Blockchain corrupted at height {}: {}", self.height, self.reason)
+    }
+}
+
+impl std::error::Error for AuditFailure {}
+
+/**
+ * Transaction with the same id is already committed
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DuplicateTransaction {
+    pub txid: TransactionId,
+    pub height: u64,
+}
+
+impl Display for DuplicateTransaction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Transaction {} is already committed in the block at height {}", self.txid, self.height)
+    }
+}
+
+impl std::error::Error for DuplicateTransaction {}
+
 impl BlockChain {
-    pub fn new(validator_public_key: &PublicKeyStr, initial_utxo: UnspentOutput) -> Self {
+    pub fn new(validator_public_key: &PublicKeyStr, genesis: Genesis) -> Self {
         Self {
-            initial_utxo,
-            blocks: vec![],
+            utxos: UtxoSet::new(&genesis.initial_utxos()),
+            tip: genesis.hash_str(),
+            genesis,
+            store: Box::new(InMemoryBlockStore::new()),
         }
     }
 
-    pub fn new_testing_only(validator_public_key: &PublicKeyStr, initial_utxo: UnspentOutput, blocks: Vec<Block>) -> Self {
+    pub fn new_testing_only(validator_public_key: &PublicKeyStr, genesis: Genesis, blocks: Vec<Block>) -> Self {
+        let mut utxos = UtxoSet::new(&genesis.initial_utxos());
+        for block in &blocks {
+            utxos.apply_block(block).expect("Blocks given for testing must be valid");
+        }
+        let tip = blocks.last().map(|block| block.hash.to_owned()).unwrap_or(genesis.hash_str());
         Self {
-            initial_utxo,
-            blocks,
+            genesis,
+            store: Box::new(InMemoryBlockStore::from(blocks)),
+            utxos,
+            tip,
         }
     }
 
-    pub fn verify_transaction(&self, transaction: &SignedBalancedTransaction) -> Result<()> {
-        // 1. make sure input amount matches output amount
-        transaction.check_balanced()?;
+    /**
+     * Creates blockchain on top of the given store.
+     * Blocks already present in the store are verified again before the blockchain is returned.
+     */
+    pub fn with_store(validator_public_key: &PublicKeyStr, genesis: Genesis, store: Box<dyn BlockStore>) -> Result<Self> {
+        let mut blockchain = Self { utxos: UtxoSet::default(), tip: genesis.hash_str(), genesis, store };
 
-        // 2. make sure there is only one address in inputs (multiple signatures are not supported yet)
-        let from_address = transaction.get_from_address()?;
+        let (utxos, tip) = blockchain.replay()?;
+        blockchain.utxos = utxos;
+        blockchain.tip = tip;
 
-        // 3. make sure signature provided is correct
-        let public_key = RsaPublicKey::try_from(from_address)?;
-        let cbor = transaction.balanced_transaction.to_cbor()?;
+        Ok(blockchain)
+    }
 
-        transaction.signature.verify(&public_key, &cbor)?;
+    /**
+     * Opens blockchain persisted in the given data directory
+     */
+    pub fn open(validator_public_key: &PublicKeyStr, genesis: Genesis, data_dir: &Path) -> Result<Self> {
+        let store = FileBlockStore::open(data_dir)?;
+        Self::with_store(validator_public_key, genesis, Box::new(store))
+    }
 
-        // 4. ensure that all input utxos are unspent
-        self.ensure_utxos_unspent(&transaction.inputs())?;
+    pub fn blocks(&self) -> impl Iterator<Item = &Block> {
+        (0..self.store.len()).filter_map(|idx| self.store.get(idx))
+    }
 
-        Ok(())
+    /**
+     * Returns block at the given height, the genesis at height 0 is not a block
+     */
+    pub fn block_at_height(&self, height: u64) -> Option<&Block> {
+        let index = height.checked_sub(1)?;
+        self.store.get(index as usize)
     }
 
-    pub fn commit_transaction(&mut self, transaction: &SignedBalancedTransaction, validator_private_key: &PrivateKeyStr) -> Result<Block> {
-        self.verify_transaction(&transaction)?;
+    pub fn last_block(&self) -> Option<&Block> {
+        self.store.last()
+    }
 
-        let previous_block_hash = if self.blocks.is_empty() {
-            self.initial_utxo.hash()
-        } else {
-            hex::decode(&self.blocks.last().unwrap().hash)?
-        };
+    /**
+     * Height of the last block, 0 if there are no blocks yet
+     */
+    pub fn height(&self) -> u64 {
+        self.store.len() as u64
+    }
 
-        let block = Block::create_block_and_sign(&previous_block_hash, transaction, validator_private_key)?;
+    pub fn len(&self) -> usize {
+        self.store.len()
+    }
 
-        self.blocks.push(block.clone());
+    pub fn is_empty(&self) -> bool {
+        self.store.is_empty()
+    }
 
-        Ok(block)
+    /**
+     * Height and the earliest timestamp of the next block, locks of transactions committed now are checked against them
+     */
+    pub fn next_block_time(&self) -> BlockTime {
+        // timestamps never go back, even if the local clock does
+        let previous_timestamp = self.last_block().map(|block| block.header.timestamp).unwrap_or(self.genesis.parameters.timestamp);
+        BlockTime { height: self.height() + 1, timestamp: now_millis().max(previous_timestamp) }
     }
 
     /**
-     * Makes sure given utxos exist and unspent
+     * Verifies the transaction can be put into the next block
      */
-    pub fn ensure_utxos_unspent(&self, utxos: &Vec<UnspentOutput>) -> Result<()> {
-        let input_utxos: HashSet<String> = HashSet::from_iter(utxos.iter().map(|utxo| utxo.hash_str()));
-        let mut remaining_utxos = HashSet::<String>::from_iter(input_utxos.clone());
+    pub fn verify_transaction(&self, transaction: &SignedBalancedTransaction) -> Result<()> {
+        self.verify_transaction_with_utxos(transaction, &self.utxos, &self.next_block_time())
+    }
+
+    fn verify_transaction_with_utxos(&self, transaction: &SignedBalancedTransaction, utxos: &UtxoSet, at: &BlockTime) -> Result<()> {
+        // 0. make sure the transaction was created for this chain
+        let chain_id = &transaction.balanced_transaction.chain_id;
+        if *chain_id != self.genesis.chain_id {
+            return Err(anyhow!("Transaction belongs to chain {}, but this is chain {}", chain_id, self.genesis.chain_id));
+        }
+
+        if transaction.is_reward() {
+            return Err(anyhow!("Only the first transaction of a block can be a reward"));
+        }
+        ensure_not_committed(transaction, utxos)?;
 
-        // check if at least one utxo has been spent
-        for block in &self.blocks {
-            for utxo in block.transaction.inputs() {
-                let hash = utxo.hash_str();
-                if input_utxos.contains(&hash) {
-                    return Err(anyhow!("Utxo {} has already been spent", hash));
+        if let Some(issuance) = &transaction.balanced_transaction.issuance {
+            issuance.validate()?;
+            if utxos.asset(&issuance.asset_id()).is_some() {
+                return Err(anyhow!("Asset {} is already issued by {}", issuance.name, issuance.issuer));
+            }
+        }
+        let minted = transaction.balanced_transaction.minted_token();
+        if let Some(mint) = &transaction.balanced_transaction.mint {
+            mint.validate()?;
+            if minted.is_none() {
+                return Err(anyhow!("Transaction minting a token must have an input"));
+            }
+            if transaction.balanced_transaction.issuance.is_some() {
+                return Err(anyhow!("Transaction can't both issue an asset and mint a token"));
+            }
+        }
+        // a token is held by a single output, exact balancing of assets then keeps it from being duplicated
+        for (index, output) in transaction.outputs().iter().enumerate() {
+            if let Some(asset) = &output.asset {
+                if output.amount != 1 && (minted.as_ref() == Some(asset) || utxos.token(asset).is_some()) {
+                    return Err(anyhow!("Token {} must be held by an output of amount 1, {} given", asset, output.amount));
                 }
             }
+            // an empty output could hold an asset or token before it exists, only the reward may pay nothing
+            if output.amount == 0 {
+                return Err(anyhow!("Output {} of the transaction has zero amount", index));
+            }
         }
 
-        // make sure all utxos exist
-        remaining_utxos.remove(&self.initial_utxo.hash_str());
-        for block in &self.blocks {
-            for utxo in block.transaction.outputs() {
-                remaining_utxos.remove(&utxo.hash_str());
-            }
+        // 1. ensure that all input utxos are unspent and look up their owners and amounts
+        let spent = utxos.resolve(transaction.inputs())?;
+        if let Some(locked) = spent.iter().find(|utxo| !utxo.is_spendable_at(at)) {
+            return Err(anyhow!("Utxo {} is locked until {}", locked.outpoint, locked.lock.map(|lock| lock.to_string()).unwrap_or_default()));
+        }
+
+        // 2. make sure outputs don't exceed inputs
+        transaction.fee(&spent)?;
+
+        // 3. make sure every owner of the inputs signed the transaction
+        transaction.verify_signatures(&spent)?;
+
+        // 4. make sure conditions of the spent script addresses are met
+        transaction.verify_scripts(at)?;
+
+        Ok(())
+    }
+
+    /**
+     * Verifies transactions which are going to be put into the same block.
+     * Transactions are verified against the unspent outputs of the blockchain, so they can't spend outputs of each other
+     * and no output can be spent by more than one of them.
+     */
+    pub fn verify_transactions(&self, transactions: &[SignedBalancedTransaction]) -> Result<()> {
+        self.verify_transactions_with_utxos(transactions, &self.utxos, &self.next_block_time())
+    }
+
+    fn verify_transactions_with_utxos(&self, transactions: &[SignedBalancedTransaction], utxos: &UtxoSet, at: &BlockTime) -> Result<()> {
+        if transactions.is_empty() {
+            return Err(anyhow!("Block must contain at least one transaction"));
+        }
+        if transactions.len() > self.genesis.parameters.max_block_transactions {
+            return Err(anyhow!("Block must contain at most {} transactions, {} found", self.genesis.parameters.max_block_transactions, transactions.len()));
         }
 
-        if !remaining_utxos.is_empty() {
-            return Err(anyhow!("Utxos not found: {}", Vec::from_iter(remaining_utxos.clone()).join(", ")));
+        for (idx, transaction) in transactions.iter().enumerate() {
+            self.verify_transaction_with_utxos(transaction, utxos, at)
+                .map_err(|err| match err.is::<DuplicateTransaction>() {
+                    true => err,
+                    false => anyhow!("Transaction {} of the block is invalid: {}", idx + 1, err),
+                })?;
         }
-        
+
+        let mut issued = HashSet::new();
+        if let Some(issuance) = transactions.iter().filter_map(|transaction| transaction.balanced_transaction.issuance.as_ref()).find(|issuance| !issued.insert(issuance.asset_id())) {
+            return Err(anyhow!("Asset {} of {} is issued more than once", issuance.name, issuance.issuer));
+        }
+
+        let all_inputs: Vec<OutPoint> = transactions.iter().flat_map(|transaction| transaction.inputs().iter().cloned()).collect();
+        utxos.ensure_unspent(&all_inputs)
+    }
+
+    /**
+     * Makes sure the block can be appended on top of the given previous block and its transactions are valid
+     */
+    fn verify_next_block(&self, block: &Block, previous_block: Option<&Block>, utxos: &UtxoSet) -> Result<()> {
+        let (height, previous_block_hash, previous_timestamp) = match previous_block {
+            Some(previous) => (previous.header.height + 1, previous.hash.to_owned(), previous.header.timestamp),
+            None => (1, self.genesis.hash_str(), self.genesis.parameters.timestamp),
+        };
+
+        block.verify_block(height, &previous_block_hash)?;
+        block.verify_signatures()?;
+        if block.header.timestamp < previous_timestamp {
+            return Err(anyhow!("block timestamp {} is earlier than timestamp of the previous block {}", block.header.timestamp, previous_timestamp));
+        }
+
+        let (reward, transactions) = block.split_reward();
+        let at = BlockTime { height: block.header.height, timestamp: block.header.timestamp };
+        self.verify_transactions_with_utxos(transactions, utxos, &at)?;
+        if let Some(reward) = reward {
+            self.verify_reward(reward, &block.header, transactions, utxos)?;
+        }
+
         Ok(())
     }
 
-    pub fn all_balances(&self) -> Vec<(PublicKeyStr, u64)> {
-        todo!()
+    /**
+     * Reward is paid to the proposer and can't exceed the fees of the block transactions plus the subsidy of the block
+     */
+    fn verify_reward(&self, reward: &SignedBalancedTransaction, header: &BlockHeader, transactions: &[SignedBalancedTransaction], utxos: &UtxoSet) -> Result<()> {
+        if reward.balanced_transaction.chain_id != self.genesis.chain_id {
+            return Err(anyhow!("Reward belongs to chain {}, but this is chain {}", reward.balanced_transaction.chain_id, self.genesis.chain_id));
+        }
+        if reward.balanced_transaction.reward_height != Some(header.height) {
+            return Err(anyhow!("Reward does not belong to the block at height {}", header.height));
+        }
+        if !reward.inputs().is_empty() || !reward.signatures.is_empty() || reward.witness != Witness::default() {
+            return Err(anyhow!("Reward must have no inputs, signatures or witness"));
+        }
+        if reward.outputs().is_empty() {
+            return Err(anyhow!("Reward has no outputs"));
+        }
+        if reward.balanced_transaction.issuance.is_some() || reward.balanced_transaction.mint.is_some() || reward.outputs().iter().any(|output| output.asset.is_some()) {
+            return Err(anyhow!("Reward must be paid in the native coin"));
+        }
+        if let Some(output) = reward.outputs().iter().find(|output| output.address != header.proposer) {
+            return Err(anyhow!("Reward is paid to {}, but the block was proposed by {}", output.address, header.proposer));
+        }
+
+        let fees = Self::total_fees(transactions, utxos)?;
+        let subsidy = self.subsidy(header.height, utxos);
+        let claimed = total_amount(reward.outputs().iter().map(|output| output.amount))?;
+        if claimed > total_amount([fees, subsidy])? {
+            return Err(anyhow!("Reward {} exceeds fees {} and subsidy {} of the block", claimed, fees, subsidy));
+        }
+
+        Ok(())
     }
 
-    pub fn blockchain_hash(&self) -> Result<String> {
-        let mut last_block_hash = Box::new(self.initial_utxo.hash());
-        for (idx, block) in self.blocks.iter().enumerate() {
-            if !block.verify_block(&last_block_hash)? {
-                return Err(anyhow::anyhow!("Blockchain corrupted at index {}. Verification failed", idx));
-            } else {
-                let block_hash = hex::decode(&block.hash)?;
-                last_block_hash = Box::new(block_hash.clone());
-            }
+    /**
+     * Subsidy of the block at the height, capped by the coins which can still be issued.
+     * Once the maximum supply is issued blocks get no subsidy, their rewards are the fees only.
+     */
+    fn subsidy(&self, height: u64, utxos: &UtxoSet) -> u64 {
+        self.genesis.subsidy(height).min(utxos.issuable())
+    }
+
+    /**
+     * Fee paid by the transaction, its inputs must be unspent
+     */
+    pub fn fee(&self, transaction: &SignedBalancedTransaction) -> Result<u64> {
+        ensure_not_committed(transaction, &self.utxos)?;
+        transaction.fee(&self.utxos.resolve(transaction.inputs())?)
+    }
+
+    fn total_fees(transactions: &[SignedBalancedTransaction], utxos: &UtxoSet) -> Result<u64> {
+        transactions.iter().try_fold(0_u64, |total, transaction| {
+            let fee = transaction.fee(&utxos.resolve(transaction.inputs())?)?;
+            total.checked_add(fee).ok_or(anyhow!("Total fee of the block is too large"))
+        })
+    }
+
+    pub fn commit_transaction(&mut self, transaction: &SignedBalancedTransaction, validator_private_key: &PrivateKeyStr) -> Result<Block> {
+        self.commit_transactions(std::slice::from_ref(transaction), validator_private_key)
+    }
+
+    /**
+     * Puts all given transactions into a single block, in the given order.
+     * Fees of the transactions and the subsidy of the block are paid to the proposer by a reward transaction put before them.
+     */
+    pub fn commit_transactions(&mut self, transactions: &[SignedBalancedTransaction], validator_private_key: &PrivateKeyStr) -> Result<Block> {
+        // locks are checked against the block the transactions are put into
+        let at = self.next_block_time();
+        self.verify_transactions_with_utxos(transactions, &self.utxos, &at)?;
+
+        let height = at.height;
+        let reward = total_amount([Self::total_fees(transactions, &self.utxos)?, self.subsidy(height, &self.utxos)])?;
+        let mut block_transactions = Vec::with_capacity(transactions.len() + 1);
+        if reward > 0 {
+            let proposer = PublicKeyStr::try_from(&RsaPrivateKey::try_from(validator_private_key)?.to_public_key())?;
+            block_transactions.push(SignedBalancedTransaction::reward(&self.genesis.chain_id, height, &proposer, reward));
+        }
+        block_transactions.extend_from_slice(transactions);
+
+        let block = Block::create_block_and_sign(height, &self.tip, at.timestamp, &block_transactions, validator_private_key)?;
+
+        self.append_verified_block(block)
+    }
+
+    /**
+     * Appends block proposed by another validator, the block is signed by this validator once it's verified
+     */
+    pub fn append_block(&mut self, mut block: Block, validator_private_key: &PrivateKeyStr) -> Result<Block> {
+        self.verify_next_block(&block, self.last_block(), &self.utxos)?;
+
+        let max_timestamp = now_millis() + MAX_CLOCK_DRIFT_MILLIS;
+        if block.header.timestamp > max_timestamp {
+            return Err(anyhow!("Block timestamp {} is too far in the future", block.header.timestamp));
         }
-        let hash = hex::encode(last_block_hash.as_slice());
-        Ok(hash)
+
+        block.sign(validator_private_key)?;
+
+        self.append_verified_block(block)
     }
 
-    pub fn index_of_block(&self, hash: &str) -> isize {
-        let mut idx = 0;
-        for block in &self.blocks {
-            if block.hash == hash {
-                return idx;
-            }
-            idx +=1;
+    fn append_verified_block(&mut self, block: Block) -> Result<Block> {
+        self.store.append(block.clone())?;
+        self.utxos.apply_block(&block)?;
+        self.tip = block.hash.to_owned();
+
+        Ok(block)
+    }
+
+    /**
+     * Adds signature of a validator to the block with the given hash, the signature must be made over the block hash.
+     * Returns the block if it's found.
+     */
+    pub fn add_validator_signature(&mut self, block_hash: &str, signature: ValidatorSignature) -> Result<Option<&Block>> {
+        let height = match self.height_of_block(block_hash) {
+            Some(height) if height > 0 => height,
+            _ => return Ok(None),
+        };
+        signature.verify(block_hash)?;
+
+        self.store.add_validator_signature((height - 1) as usize, signature)?;
+
+        Ok(self.block_at_height(height))
+    }
+
+    /**
+     * Makes sure given utxos exist and unspent
+     */
+    pub fn ensure_utxos_unspent(&self, outpoints: &[OutPoint]) -> Result<()> {
+        self.utxos.ensure_unspent(outpoints)
+    }
+
+    /**
+     * Returns the block all given transactions are committed in, if they are committed in the same block.
+     * Lets resubmitted transactions be recognized instead of being rejected.
+     */
+    pub fn committed_block(&self, transactions: &[SignedBalancedTransaction]) -> Option<&Block> {
+        let heights: Vec<Option<u64>> = transactions.iter().map(|transaction| self.utxos.height_of_transaction(&transaction.id())).collect();
+        match heights.first() {
+            Some(Some(height)) if heights.iter().all(|other| *other == Some(*height)) => self.block_at_height(*height),
+            _ => None,
         }
-        -1
     }
-}
\ No newline at end of file
+
+    pub fn utxos(&self) -> &UtxoSet {
+        &self.utxos
+    }
+
+    /**
+     * Returns spendable amount of every address, ordered by address
+     */
+    pub fn all_balances(&self) -> Result<Vec<(PublicKeyStr, u64)>> {
+        self.sorted_balances(|balance| balance.amount)
+    }
+
+    /**
+     * Returns amount which can't be spent by the next block yet, only addresses owning locked outputs are returned
+     */
+    pub fn all_locked_balances(&self) -> Result<Vec<(PublicKeyStr, u64)>> {
+        Ok(self.sorted_balances(|balance| balance.locked)?.into_iter().filter(|(_, locked)| *locked > 0).collect())
+    }
+
+    /**
+     * Returns spendable amount of every asset other than the native coin, sorted by address and asset
+     */
+    pub fn all_asset_balances(&self) -> Result<Vec<(PublicKeyStr, AssetId, u64)>> {
+        self.sorted_asset_balances(|balance| &balance.assets)
+    }
+
+    /**
+     * Returns amount of every asset other than the native coin which can't be spent by the next block yet
+     */
+    pub fn all_locked_asset_balances(&self) -> Result<Vec<(PublicKeyStr, AssetId, u64)>> {
+        self.sorted_asset_balances(|balance| &balance.locked_assets)
+    }
+
+    fn sorted_asset_balances(&self, assets: impl Fn(&Balance) -> &BTreeMap<AssetId, u64>) -> Result<Vec<(PublicKeyStr, AssetId, u64)>> {
+        let mut balances: Vec<(PublicKeyStr, AssetId, u64)> = self.utxos.balances(&self.next_block_time())?.into_iter()
+            .flat_map(|(address, balance)| assets(&balance).iter().map(|(asset, amount)| (address.clone(), asset.clone(), *amount)).collect::<Vec<_>>())
+            .collect();
+        balances.sort_by(|(a, a_asset, _), (b, b_asset, _)| (&a.0.0, a_asset).cmp(&(&b.0.0, b_asset)));
+        Ok(balances)
+    }
+
+    fn sorted_balances(&self, amount: impl Fn(&Balance) -> u64) -> Result<Vec<(PublicKeyStr, u64)>> {
+        let mut balances: Vec<(PublicKeyStr, u64)> = self.utxos.balances(&self.next_block_time())?.into_iter()
+            .map(|(address, balance)| (address, amount(&balance)))
+            .collect();
+        balances.sort_by(|(a, _), (b, _)| a.0.0.cmp(&b.0.0));
+        Ok(balances)
+    }
+
+    /**
+     * Native coins in existence, outputs which can't be spent by the next block are counted as locked
+     */
+    pub fn supply(&self) -> Result<Supply> {
+        self.utxos.supply(&self.next_block_time())
+    }
+
+    /**
+     * Balance of the address, outputs which can't be spent by the next block are counted as locked
+     */
+    pub fn balance(&self, address: &PublicKeyStr) -> Result<Balance> {
+        self.utxos.balance(address, &self.next_block_time())
+    }
+
+    /**
+     * Hash of the last block, blocks are verified as they are appended so it's not recomputed
+     */
+    pub fn blockchain_hash(&self) -> String {
+        self.tip.to_owned()
+    }
+
+    /**
+     * Verifies the entire blockchain from the genesis: hashes of all blocks and all transactions.
+     * Returns the first block which failed the verification.
+     */
+    pub fn audit(&self) -> Result<(), AuditFailure> {
+        self.replay().map(|_| ())
+    }
+
+    /**
+     * Verifies all blocks one by one, returns the utxo set and the hash of the last block
+     */
+    fn replay(&self) -> Result<(UtxoSet, String), AuditFailure> {
+        let mut utxos = UtxoSet::new(&self.genesis.initial_utxos());
+        let mut previous_block: Option<&Block> = None;
+
+        for block in self.blocks() {
+            let height = previous_block.map(|previous| previous.header.height + 1).unwrap_or(1);
+
+            self.verify_next_block(block, previous_block, &utxos)
+                .map_err(|err| AuditFailure { height, reason: err.to_string() })?;
+
+            utxos.apply_block(block)
+                .map_err(|err| AuditFailure { height, reason: err.to_string() })?;
+            previous_block = Some(block);
+        }
+
+        let tip = previous_block.map(|block| block.hash.to_owned()).unwrap_or(self.genesis.hash_str());
+        Ok((utxos, tip))
+    }
+
+    /**
+     * Returns height of the block with the given hash, hash of the genesis is at height 0
+     */
+    pub fn height_of_block(&self, hash: &str) -> Option<u64> {
+        if self.genesis.hash_str() == hash {
+            return Some(0);
+        }
+        // recent blocks are looked up most of the time
+        (0..self.store.len()).rev()
+            .filter_map(|idx| self.store.get(idx))
+            .find(|block| block.hash == hash)
+            .map(|block| block.header.height)
+    }
+}
+
+fn ensure_not_committed(transaction: &SignedBalancedTransaction, utxos: &UtxoSet) -> Result<()> {
+    let txid = transaction.id();
+    match utxos.height_of_transaction(&txid) {
+        Some(height) => Err(DuplicateTransaction { txid, height }.into()),
+        None => Ok(()),
+    }
+}
@@ -0,0 +1,366 @@
+#[cfg(test)]
+mod tests {
+    use crate::{blockchain::{transaction::Transaction, coin_selection::CoinSelection, blockchain::{BlockChain, DuplicateTransaction}, genesis::{Genesis, GenesisAllocation, Emission}, block::Block, validator_signature::ValidatorSignature, utxo::{TimeLock, TransactionOutput}, utxo_set::{Balance, Supply}, balanced_transaction::BalancedTransaction}, encryption::generate_rsa_keypair_custom};
+
+    #[test]
+    fn audit_reports_first_corrupted_block() {
+        let (validator_private_key, validator_public_key) = &generate_rsa_keypair_custom().unwrap();
+        let (priv_1, pub_1) = &generate_rsa_keypair_custom().unwrap();
+        let (_, pub_2) = &generate_rsa_keypair_custom().unwrap();
+        let genesis = Genesis::new("test", vec![GenesisAllocation::new(pub_1, 10)], Vec::new());
+
+        let mut blockchain = BlockChain::new(validator_public_key, genesis.clone());
+        assert_eq!(blockchain.blockchain_hash(), genesis.hash_str(), "Hash of an empty blockchain is wrong");
+
+        let mut blocks = Vec::new();
+        for amount in [1, 2, 3] {
+            let block = Transaction::new(pub_1, pub_2, amount)
+                .balance_transaction(&blockchain, 0, CoinSelection::default())
+                .unwrap()
+                .sign(&priv_1.try_into().unwrap())
+                .unwrap()
+                .commit(&mut blockchain, validator_private_key)
+                .unwrap();
+            assert_eq!(blockchain.blockchain_hash(), block.hash, "Blockchain tip is not advanced");
+            blocks.push(block);
+        }
+        assert_eq!(blockchain.audit(), Ok(()));
+
+        // transaction of the second block is tampered with
+        let mut corrupted_blocks = blocks.clone();
+        corrupted_blocks[1].transactions[0].balanced_transaction.outputs[0].amount += 1;
+        let failure = BlockChain::new_testing_only(validator_public_key, genesis.clone(), corrupted_blocks).audit().unwrap_err();
+        assert_eq!(failure.height, 2, "Corrupted height is wrong");
+
+        // blocks are reordered
+        let mut corrupted_blocks = blocks.clone();
+        corrupted_blocks.swap(0, 2);
+        let failure = BlockChain::new_testing_only(validator_public_key, genesis.clone(), corrupted_blocks).audit().unwrap_err();
+        assert_eq!(failure.height, 1, "Corrupted height is wrong");
+        assert!(failure.reason.contains("does not match"), "Reason is wrong: {}", failure.reason);
+
+        // block content is kept but it's put on top of another block
+        let mut corrupted_blocks = blocks;
+        corrupted_blocks.remove(1);
+        let failure = BlockChain::new_testing_only(validator_public_key, genesis, corrupted_blocks).audit().unwrap_err();
+        assert_eq!(failure.height, 2, "Corrupted height is wrong");
+    }
+
+    #[test]
+    fn several_transactions_are_committed_in_one_block() {
+        let (validator_private_key, validator_public_key) = &generate_rsa_keypair_custom().unwrap();
+        let (priv_1, pub_1) = &generate_rsa_keypair_custom().unwrap();
+        let (priv_2, pub_2) = &generate_rsa_keypair_custom().unwrap();
+        let (_, pub_3) = &generate_rsa_keypair_custom().unwrap();
+
+        let mut blockchain = BlockChain::new(validator_public_key, Genesis::new("test", vec![GenesisAllocation::new(pub_1, 10)], Vec::new()));
+        Transaction::new(pub_1, pub_2, 4)
+            .balance_transaction(&blockchain, 0, CoinSelection::default())
+            .unwrap()
+            .sign(&priv_1.try_into().unwrap())
+            .unwrap()
+            .commit(&mut blockchain, validator_private_key)
+            .unwrap();
+
+        let transaction_1 = Transaction::new(pub_1, pub_3, 6).balance_transaction(&blockchain, 0, CoinSelection::default()).unwrap().sign(&priv_1.try_into().unwrap()).unwrap();
+        let transaction_2 = Transaction::new(pub_2, pub_3, 4).balance_transaction(&blockchain, 0, CoinSelection::default()).unwrap().sign(&priv_2.try_into().unwrap()).unwrap();
+
+        // the same output can't be spent twice within a block
+        let err = blockchain.commit_transactions(&[transaction_1.clone(), transaction_1.clone()], validator_private_key).unwrap_err();
+        assert!(err.to_string().contains("is used more than once"), "Error is wrong: {}", err);
+        assert_eq!(blockchain.len(), 1, "Rejected block must not be appended");
+
+        let block = blockchain.commit_transactions(&[transaction_1, transaction_2], validator_private_key).unwrap();
+        assert_eq!(block.transactions.len(), 2, "Number of transactions in the block is wrong");
+        assert_eq!(blockchain.len(), 2, "Number of blocks is wrong");
+        assert_eq!(blockchain.balance(pub_3).unwrap().amount, 10, "Balance of the receiver is wrong");
+        assert_eq!(blockchain.audit(), Ok(()));
+
+        // transactions of the block are reordered, merkle root does not match anymore
+        let mut corrupted_block = block;
+        corrupted_block.transactions.swap(0, 1);
+        assert!(corrupted_block.verify_block(2, &blockchain.block_at_height(1).unwrap().hash).is_err(), "Reordered block must not be valid");
+    }
+
+    #[test]
+    fn batch_of_different_owners_is_signed_by_owners_of_inputs() {
+        let (validator_private_key, validator_public_key) = &generate_rsa_keypair_custom().unwrap();
+        let (priv_1, pub_1) = &generate_rsa_keypair_custom().unwrap();
+        let (priv_2, pub_2) = &generate_rsa_keypair_custom().unwrap();
+        let (_, pub_3) = &generate_rsa_keypair_custom().unwrap();
+        let mut blockchain = BlockChain::new(validator_public_key, Genesis::new("test", vec![GenesisAllocation::new(pub_1, 10), GenesisAllocation::new(pub_2, 5)], Vec::new()));
+        let private_keys = [priv_1.try_into().unwrap(), priv_2.try_into().unwrap()];
+
+        let balanced_1 = Transaction::new(pub_1, pub_3, 6).balance_transaction(&blockchain, 0, CoinSelection::default()).unwrap();
+        let balanced_2 = Transaction::new(pub_2, pub_3, 4).balance_transaction(&blockchain, 0, CoinSelection::default()).unwrap();
+
+        // a key owning none of the inputs must not sign
+        let err = blockchain.commit_transactions(&[balanced_1.sign_with(&private_keys).unwrap()], validator_private_key).unwrap_err();
+        assert!(err.to_string().contains("owns none of its inputs"), "Error is wrong: {}", err);
+
+        let transactions: Vec<_> = [balanced_1, balanced_2].iter().map(|balanced| {
+            let owners: Vec<_> = blockchain.utxos().resolve(&balanced.inputs).unwrap().into_iter().map(|utxo| utxo.address).collect();
+            balanced.sign_by_owners(&owners, &private_keys, &Default::default()).unwrap()
+        }).collect();
+        assert_eq!(transactions[0].signatures.len(), 1, "Only the owner of the inputs must sign");
+
+        blockchain.commit_transactions(&transactions, validator_private_key).unwrap();
+        assert_eq!(blockchain.balance(pub_3).unwrap().amount, 10, "Balance of the receiver is wrong");
+        assert_eq!(blockchain.audit(), Ok(()));
+    }
+
+    #[test]
+    fn block_of_another_validator_is_appended_with_its_header() {
+        let (proposer_private_key, proposer_public_key) = &generate_rsa_keypair_custom().unwrap();
+        let (follower_private_key, follower_public_key) = &generate_rsa_keypair_custom().unwrap();
+        let (priv_1, pub_1) = &generate_rsa_keypair_custom().unwrap();
+        let (_, pub_2) = &generate_rsa_keypair_custom().unwrap();
+        let genesis = Genesis::new("test", vec![GenesisAllocation::new(pub_1, 10)], Vec::new());
+
+        let mut proposer = BlockChain::new(proposer_public_key, genesis.clone());
+        let mut follower = BlockChain::new(follower_public_key, genesis.clone());
+
+        let mut blocks = Vec::new();
+        for amount in [1, 2] {
+            let block = Transaction::new(pub_1, pub_2, amount)
+                .balance_transaction(&proposer, 0, CoinSelection::default())
+                .unwrap()
+                .sign(&priv_1.try_into().unwrap())
+                .unwrap()
+                .commit(&mut proposer, proposer_private_key)
+                .unwrap();
+            blocks.push(block);
+        }
+        assert_eq!(blocks[0].header.height, 1, "Height of the first block is wrong");
+        assert_eq!(blocks[0].header.prev_hash, genesis.hash_str(), "First block must follow the genesis");
+        assert_eq!(blocks[1].header.prev_hash, blocks[0].hash, "Previous hash is wrong");
+        assert_eq!(&blocks[1].header.proposer, proposer_public_key, "Proposer is wrong");
+        assert!(blocks[1].header.timestamp >= blocks[0].header.timestamp, "Timestamps must not go back");
+
+        // blocks can't be skipped
+        let err = follower.append_block(blocks[1].clone(), follower_private_key).unwrap_err();
+        assert!(err.to_string().contains("does not match expected height"), "Error is wrong: {}", err);
+
+        for block in &blocks {
+            let appended = follower.append_block(block.clone(), follower_private_key).unwrap();
+            assert_eq!(appended.hash, block.hash, "Appended block must keep hash of the proposer");
+            assert_eq!(appended.validator_signatures().len(), 2, "Block must be signed by proposer and follower");
+        }
+        assert_eq!(follower.blockchain_hash(), proposer.blockchain_hash(), "Blockchains diverged");
+        assert_eq!(follower.height(), 2, "Height is wrong");
+        assert_eq!(follower.height_of_block(&blocks[1].hash), Some(2), "Height of the block is wrong");
+        assert_eq!(follower.height_of_block(&genesis.hash_str()), Some(0), "Genesis must be at height 0");
+        assert_eq!(follower.height_of_block("unknown"), None);
+        assert!(follower.block_at_height(0).is_none(), "Genesis is not a block");
+    }
+
+    #[test]
+    fn signatures_must_cover_block_hash() {
+        let (proposer_private_key, proposer_public_key) = &generate_rsa_keypair_custom().unwrap();
+        let (follower_private_key, follower_public_key) = &generate_rsa_keypair_custom().unwrap();
+        let (priv_1, pub_1) = &generate_rsa_keypair_custom().unwrap();
+        let (_, pub_2) = &generate_rsa_keypair_custom().unwrap();
+        let genesis = Genesis::new("test", vec![GenesisAllocation::new(pub_1, 10)], Vec::new());
+
+        let mut proposer = BlockChain::new(proposer_public_key, genesis.clone());
+        let mut follower = BlockChain::new(follower_public_key, genesis);
+        let block = Transaction::new(pub_1, pub_2, 1)
+            .balance_transaction(&proposer, 0, CoinSelection::default())
+            .unwrap()
+            .sign(&priv_1.try_into().unwrap())
+            .unwrap()
+            .commit(&mut proposer, proposer_private_key)
+            .unwrap();
+        assert!(block.verify_signatures().is_ok(), "Signature of the proposer must be valid");
+
+        // signature made by a key other than the named one
+        let forged_signature = block.clone().sign(follower_private_key).unwrap();
+        let forged_signature = ValidatorSignature::new(proposer_public_key, &forged_signature.validator_signature);
+        let err = proposer.add_validator_signature(&block.hash, forged_signature.clone()).unwrap_err();
+        assert!(err.to_string().contains("is invalid for block"), "Error is wrong: {}", err);
+
+        // block which is only signed by a validator other than the proposer
+        let mut unsigned_block = Block::new(1, &block.header.prev_hash, block.header.timestamp, proposer_public_key, &block.transactions).unwrap();
+        unsigned_block.add_validator_signature(forged_signature);
+        assert!(follower.append_block(unsigned_block, follower_private_key).is_err(), "Forged block must be rejected");
+
+        let appended = follower.append_block(block, follower_private_key).unwrap();
+        let follower_signature = appended.signature_of(follower_public_key).unwrap().clone();
+        assert!(proposer.add_validator_signature(&appended.hash, follower_signature).unwrap().is_some(), "Block is not found");
+        assert_eq!(proposer.last_block().unwrap().validator_signatures().len(), 2, "Number of signatures is wrong");
+    }
+
+    #[test]
+    fn transaction_of_another_chain_is_rejected() {
+        let (validator_private_key, validator_public_key) = &generate_rsa_keypair_custom().unwrap();
+        let (priv_1, pub_1) = &generate_rsa_keypair_custom().unwrap();
+        let (_, pub_2) = &generate_rsa_keypair_custom().unwrap();
+        let genesis = Genesis::new("test", vec![GenesisAllocation::new(pub_1, 10)], Vec::new());
+        let other_genesis = Genesis { chain_id: "other".to_owned(), ..genesis.clone() };
+
+        let blockchain = BlockChain::new(validator_public_key, genesis);
+        let mut other_blockchain = BlockChain::new(validator_public_key, other_genesis);
+
+        // both chains have the same allocations, so the inputs exist in both of them
+        let transaction = Transaction::new(pub_1, pub_2, 5)
+            .balance_transaction(&blockchain, 0, CoinSelection::default())
+            .unwrap()
+            .sign(&priv_1.try_into().unwrap())
+            .unwrap();
+        let err = transaction.commit(&mut other_blockchain, validator_private_key).unwrap_err();
+        assert!(err.to_string().contains("Transaction belongs to chain test, but this is chain other"), "Error is wrong: {}", err);
+        assert!(other_blockchain.is_empty(), "Transaction must not be committed");
+    }
+
+    #[test]
+    fn fees_are_paid_to_proposer() {
+        let (validator_private_key, validator_public_key) = &generate_rsa_keypair_custom().unwrap();
+        let (priv_1, pub_1) = &generate_rsa_keypair_custom().unwrap();
+        let (priv_2, pub_2) = &generate_rsa_keypair_custom().unwrap();
+        let genesis = Genesis::new("test", vec![GenesisAllocation::new(pub_1, 10)], Vec::new());
+
+        let mut blockchain = BlockChain::new(validator_public_key, genesis.clone());
+        let transaction = Transaction::new(pub_1, pub_2, 4).balance_transaction(&blockchain, 1, CoinSelection::default()).unwrap().sign(&priv_1.try_into().unwrap()).unwrap();
+        assert_eq!(blockchain.fee(&transaction).unwrap(), 3, "Fee must cover the input and both outputs");
+
+        let block = blockchain.commit_transactions(std::slice::from_ref(&transaction), validator_private_key).unwrap();
+        let (reward, transactions) = block.split_reward();
+        assert_eq!(reward.unwrap().outputs()[0].amount, 3, "Reward is wrong");
+        assert_eq!(transactions.len(), 1, "Number of transactions in the block is wrong");
+        assert_eq!(blockchain.balance(validator_public_key).unwrap().amount, 3, "Reward is not paid to the proposer");
+        assert_eq!(blockchain.audit(), Ok(()));
+
+        // change not worth its own output is left to the validator
+        let transaction = Transaction::new(pub_2, pub_1, 1).balance_transaction(&blockchain, 1, CoinSelection::default()).unwrap();
+        assert_eq!(transaction.outputs.len(), 1, "Change output must be dropped");
+
+        // proposer can't claim more than the fees
+        let mut overclaimed = block.transactions.clone();
+        overclaimed[0].balanced_transaction.outputs[0].amount += 1;
+        let (other_private_key, other_public_key) = &generate_rsa_keypair_custom().unwrap();
+        let block = Block::create_block_and_sign(1, &genesis.hash_str(), block.header.timestamp, &overclaimed, validator_private_key).unwrap();
+        let err = BlockChain::new(other_public_key, genesis).append_block(block, other_private_key).unwrap_err();
+        assert!(err.to_string().contains("exceeds fees"), "Error is wrong: {}", err);
+    }
+
+    #[test]
+    fn rewards_claim_subsidy_of_emission_schedule() {
+        let halving = Emission::Halving { initial_subsidy: 8, interval: 2 };
+        let subsidies: Vec<u64> = (1..=9).map(|height| halving.subsidy(height)).collect();
+        assert_eq!(subsidies, vec![8, 8, 4, 4, 2, 2, 1, 1, 0], "Subsidy must halve every interval");
+        assert_eq!(halving.subsidy(u64::MAX), 0, "Subsidy must drop to zero");
+
+        let (validator_private_key, validator_public_key) = &generate_rsa_keypair_custom().unwrap();
+        let (priv_1, pub_1) = &generate_rsa_keypair_custom().unwrap();
+        let (_, pub_2) = &generate_rsa_keypair_custom().unwrap();
+        let mut genesis = Genesis::new("test", vec![GenesisAllocation::new(pub_1, 10)], Vec::new());
+        genesis.parameters.emission = Some(halving);
+
+        let mut blockchain = BlockChain::new(validator_public_key, genesis.clone());
+        let block = Transaction::new(pub_1, pub_2, 4).balance_transaction(&blockchain, 1, CoinSelection::default()).unwrap()
+            .sign(&priv_1.try_into().unwrap()).unwrap()
+            .commit(&mut blockchain, validator_private_key).unwrap();
+        assert_eq!(blockchain.balance(validator_public_key).unwrap().amount, 11, "Reward must be the fees plus the subsidy");
+        assert_eq!(blockchain.supply().unwrap(), Supply { circulating: 18, locked: 0, issued: 18 }, "Supply is wrong");
+        assert_eq!(blockchain.audit(), Ok(()));
+
+        let mut overclaimed = block.transactions.clone();
+        overclaimed[0].balanced_transaction.outputs[0].amount += 1;
+        let (other_private_key, other_public_key) = &generate_rsa_keypair_custom().unwrap();
+        let block = Block::create_block_and_sign(1, &genesis.hash_str(), block.header.timestamp, &overclaimed, validator_private_key).unwrap();
+        let err = BlockChain::new(other_public_key, genesis).append_block(block, other_private_key).unwrap_err();
+        assert_eq!(err.to_string(), "Reward 12 exceeds fees 3 and subsidy 8 of the block");
+    }
+
+    #[test]
+    fn emission_never_overflows_supply() {
+        let (validator_private_key, validator_public_key) = &generate_rsa_keypair_custom().unwrap();
+        let (priv_1, pub_1) = &generate_rsa_keypair_custom().unwrap();
+        let (_, pub_2) = &generate_rsa_keypair_custom().unwrap();
+        let mut genesis = Genesis::new("test", vec![GenesisAllocation::new(pub_1, 10)], Vec::new());
+
+        genesis.parameters.emission = Some(Emission::Fixed { subsidy: u64::MAX });
+        let err = genesis.validate().unwrap_err();
+        assert!(err.to_string().contains("exceed the maximum supply"), "Error is wrong: {}", err);
+        genesis.parameters.emission = Some(Emission::Halving { initial_subsidy: u64::MAX / 2, interval: 2 });
+        assert!(genesis.validate().is_err(), "Total of the halving emission must fit into u64");
+
+        // the first block issues everything up to the limit, the second one gets no subsidy but is still accepted
+        genesis.parameters.emission = Some(Emission::Fixed { subsidy: u64::MAX - 10 });
+        genesis.validate().unwrap();
+        let mut blockchain = BlockChain::new(validator_public_key, genesis.clone());
+        Transaction::new(pub_1, pub_2, 4).balance_transaction(&blockchain, 0, CoinSelection::default()).unwrap()
+            .sign(&priv_1.try_into().unwrap()).unwrap()
+            .commit(&mut blockchain, validator_private_key).unwrap();
+        assert_eq!(blockchain.supply().unwrap(), Supply { circulating: u64::MAX, locked: 0, issued: u64::MAX }, "Supply is wrong");
+
+        let block = Transaction::new(pub_1, pub_2, 1).balance_transaction(&blockchain, 1, CoinSelection::default()).unwrap()
+            .sign(&priv_1.try_into().unwrap()).unwrap()
+            .commit(&mut blockchain, validator_private_key).unwrap();
+        let (reward, _) = block.split_reward();
+        assert_eq!(reward.unwrap().outputs()[0].amount, 3, "Reward must be the fees only");
+        assert_eq!(blockchain.len(), 2, "Block without subsidy must be appended");
+        assert_eq!(blockchain.supply().unwrap(), Supply { circulating: u64::MAX, locked: 0, issued: u64::MAX }, "Supply is wrong");
+        assert_eq!(blockchain.audit(), Ok(()));
+
+        // a reward claiming a subsidy beyond the maximum supply is rejected
+        let mut overclaimed = block.transactions.clone();
+        overclaimed[0].balanced_transaction.outputs[0].amount += 1;
+        let (other_private_key, other_public_key) = &generate_rsa_keypair_custom().unwrap();
+        let mut other = BlockChain::new(other_public_key, genesis);
+        other.append_block(blockchain.block_at_height(1).unwrap().clone(), other_private_key).unwrap();
+        let block = Block::create_block_and_sign(2, &other.blockchain_hash(), block.header.timestamp, &overclaimed, validator_private_key).unwrap();
+        let err = other.append_block(block, other_private_key).unwrap_err();
+        assert_eq!(err.to_string(), "Reward 4 exceeds fees 3 and subsidy 0 of the block");
+    }
+
+    #[test]
+    fn committed_transaction_is_not_committed_again() {
+        let (validator_private_key, validator_public_key) = &generate_rsa_keypair_custom().unwrap();
+        let (priv_1, pub_1) = &generate_rsa_keypair_custom().unwrap();
+        let (_, pub_2) = &generate_rsa_keypair_custom().unwrap();
+
+        let mut blockchain = BlockChain::new(validator_public_key, Genesis::new("test", vec![GenesisAllocation::new(pub_1, 10)], Vec::new()));
+        let transaction = Transaction::new(pub_1, pub_2, 4).balance_transaction(&blockchain, 0, CoinSelection::default()).unwrap().sign(&priv_1.try_into().unwrap()).unwrap();
+        assert!(blockchain.committed_block(std::slice::from_ref(&transaction)).is_none(), "Transaction is not committed yet");
+
+        let block = transaction.commit(&mut blockchain, validator_private_key).unwrap();
+        assert_eq!(blockchain.committed_block(std::slice::from_ref(&transaction)).map(|block| &block.hash), Some(&block.hash), "Block of the transaction is wrong");
+
+        let err = transaction.commit(&mut blockchain, validator_private_key).unwrap_err();
+        assert_eq!(err.downcast_ref::<DuplicateTransaction>(), Some(&DuplicateTransaction { txid: transaction.id(), height: 1 }), "Error is wrong: {}", err);
+        assert_eq!(blockchain.len(), 1, "Duplicate must not be committed");
+    }
+
+    #[test]
+    fn locked_outputs_are_spent_only_once_unlocked() {
+        let (validator_private_key, validator_public_key) = &generate_rsa_keypair_custom().unwrap();
+        let (priv_1, pub_1) = &generate_rsa_keypair_custom().unwrap();
+        let (priv_2, pub_2) = &generate_rsa_keypair_custom().unwrap();
+        let vesting = GenesisAllocation { lock: Some(TimeLock::Timestamp(u64::MAX)), ..GenesisAllocation::new(pub_1, 5) };
+        let mut blockchain = BlockChain::new(validator_public_key, Genesis::new("test", vec![GenesisAllocation::new(pub_1, 10), vesting], Vec::new()));
+        assert_eq!(blockchain.balance(pub_1).unwrap(), Balance { amount: 10, utxos: 1, locked: 5, ..Balance::default() }, "Vested allocation must be locked");
+
+        Transaction::new(pub_1, pub_2, 4).with_lock(Some(TimeLock::Height(3)))
+            .balance_transaction(&blockchain, 0, CoinSelection::default()).unwrap()
+            .sign(&priv_1.try_into().unwrap()).unwrap()
+            .commit(&mut blockchain, validator_private_key).unwrap();
+        assert_eq!(blockchain.balance(pub_2).unwrap(), Balance { locked: 4, ..Balance::default() }, "Balance of the recipient is wrong");
+        assert!(Transaction::new(pub_2, pub_1, 1).balance_transaction(&blockchain, 0, CoinSelection::default()).is_err(), "Locked output must not be selected");
+
+        let locked = blockchain.utxos().unspent_for(pub_2).next().unwrap().outpoint.clone();
+        let spending = BalancedTransaction { chain_id: "test".to_owned(), inputs: vec![locked.clone()], outputs: vec![TransactionOutput::new(pub_1, 4)], reward_height: None, issuance: None, mint: None }
+            .sign(&priv_2.try_into().unwrap()).unwrap();
+        let err = blockchain.verify_transaction(&spending).unwrap_err();
+        assert_eq!(err.to_string(), format!("Utxo {} is locked until height 3", locked), "Error is wrong");
+
+        // the next block is at the lock height
+        Transaction::new(pub_1, pub_1, 1)
+            .balance_transaction(&blockchain, 0, CoinSelection::default()).unwrap()
+            .sign(&priv_1.try_into().unwrap()).unwrap()
+            .commit(&mut blockchain, validator_private_key).unwrap();
+        spending.commit(&mut blockchain, validator_private_key).unwrap();
+        assert_eq!(blockchain.balance(pub_2).unwrap(), Balance::default(), "Unlocked output must be spent");
+        assert_eq!(blockchain.audit(), Ok(()));
+    }
+}
@@ -1,3 +1,4 @@
+use anyhow::Result;
 use sha1::Digest;
 use sha2::Sha256;
 
@@ -8,8 +9,11 @@ impl Cbor {
         Cbor(cbor.to_owned())
     }
 
-    pub fn hash(&self) -> Vec<u8> {
-        let digest = Sha256::digest(&self.0);
-        digest.to_vec()
+    /**
+     * Hash of the CBOR bytes, not of their hex encoding
+     */
+    pub fn hash(&self) -> Result<Vec<u8>> {
+        let bytes = hex::decode(&self.0)?;
+        Ok(Sha256::digest(bytes).to_vec())
     }
 }
\ No newline at end of file
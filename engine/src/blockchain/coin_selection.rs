@@ -0,0 +1,156 @@
+use std::{cmp::Reverse, fmt::Display, str::FromStr};
+
+use anyhow::anyhow;
+
+use super::utxo::UnspentOutput;
+
+// branch and bound gives up after exploring this many branches
+const MAX_BRANCH_AND_BOUND_TRIES: usize = 100_000;
+
+/**
+ * Strategy choosing which unspent outputs fund a transaction
+ */
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CoinSelection {
+    /**
+     * Largest outputs first, so the transaction has as few inputs as possible
+     */
+    #[default]
+    LargestFirst,
+    /**
+     * Smallest outputs first, consolidates dust into fewer outputs
+     */
+    SmallestFirst,
+    /**
+     * Outputs in the order they were created
+     */
+    OldestFirst,
+    /**
+     * Looks for outputs matching the amount exactly, so that no change output is needed.
+     * Falls back to largest first if there is no exact match.
+     */
+    BranchAndBound,
+}
+
+impl CoinSelection {
+    /**
+     * Selects outputs covering `target` and `input_cost` for every selected output.
+     * An excess below `change_cost` is not worth a change output, branch and bound accepts it as an exact match.
+     * `utxos` must be ordered oldest first, None is returned if they are not enough.
+     */
+    pub fn select(&self, utxos: &[&UnspentOutput], target: u64, input_cost: u64, change_cost: u64) -> Option<Vec<UnspentOutput>> {
+        let mut ordered = utxos.to_vec();
+        match self {
+            CoinSelection::LargestFirst => ordered.sort_by_key(|utxo| Reverse(utxo.amount)),
+            CoinSelection::SmallestFirst => ordered.sort_by_key(|utxo| utxo.amount),
+            CoinSelection::OldestFirst => (),
+            CoinSelection::BranchAndBound => {
+                return BranchAndBound::new(utxos, target, input_cost, change_cost).search()
+                    .or_else(|| CoinSelection::LargestFirst.select(utxos, target, input_cost, change_cost));
+            },
+        }
+
+        let mut amt = 0_u64;
+        let mut selected = Vec::new();
+        for utxo in ordered {
+            if amt >= target.saturating_add(input_cost.saturating_mul(selected.len() as u64)) {
+                break;
+            }
+            amt += utxo.amount;
+            selected.push(utxo.clone());
+        }
+
+        if amt >= target.saturating_add(input_cost.saturating_mul(selected.len() as u64)) {
+            Some(selected)
+        } else {
+            None
+        }
+    }
+}
+
+/**
+ * Depth first search over outputs ordered by their value minus the cost of spending them
+ */
+struct BranchAndBound<'a> {
+    candidates: Vec<(u64, &'a UnspentOutput)>,
+    // sum of values of the candidates starting at the index
+    remaining: Vec<u64>,
+    target: u64,
+    upper_bound: u64,
+    tries: usize,
+}
+
+impl<'a> BranchAndBound<'a> {
+    fn new(utxos: &[&'a UnspentOutput], target: u64, input_cost: u64, change_cost: u64) -> Self {
+        let mut candidates: Vec<(u64, &UnspentOutput)> = utxos.iter()
+            .filter(|utxo| utxo.amount > input_cost)
+            .map(|utxo| (utxo.amount - input_cost, *utxo))
+            .collect();
+        candidates.sort_by_key(|(value, _)| Reverse(*value));
+
+        let mut remaining = vec![0_u64; candidates.len() + 1];
+        for idx in (0..candidates.len()).rev() {
+            remaining[idx] = remaining[idx + 1].saturating_add(candidates[idx].0);
+        }
+
+        BranchAndBound { candidates, remaining, target, upper_bound: target.saturating_add(change_cost), tries: 0 }
+    }
+
+    fn search(&mut self) -> Option<Vec<UnspentOutput>> {
+        let mut selected: Vec<usize> = Vec::new();
+        // branches left to explore: next candidate, sum so far, selected candidates before the branch and whether the previous candidate is selected,
+        // an explicit stack so that the depth isn't limited by the thread stack when there are many outputs
+        let mut branches = vec![(0_usize, 0_u64, 0_usize, false)];
+        while let Some((idx, sum, depth, include_previous)) = branches.pop() {
+            selected.truncate(depth);
+            if include_previous {
+                selected.push(idx - 1);
+            }
+
+            self.tries += 1;
+            if self.tries > MAX_BRANCH_AND_BOUND_TRIES {
+                return None;
+            }
+            if sum > self.upper_bound {
+                continue;
+            }
+            if sum >= self.target {
+                return Some(selected.into_iter().map(|idx| self.candidates[idx].1.clone()).collect());
+            }
+            if idx == self.candidates.len() || sum.saturating_add(self.remaining[idx]) < self.target {
+                continue;
+            }
+
+            // branch including the candidate is popped first
+            branches.push((idx + 1, sum, selected.len(), false));
+            branches.push((idx + 1, sum.saturating_add(self.candidates[idx].0), selected.len(), true));
+        }
+        None
+    }
+}
+
+impl Display for CoinSelection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            CoinSelection::LargestFirst => "largest-first",
+            CoinSelection::SmallestFirst => "smallest-first",
+            CoinSelection::OldestFirst => "oldest-first",
+            CoinSelection::BranchAndBound => "branch-and-bound",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for CoinSelection {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "largest-first" => Ok(CoinSelection::LargestFirst),
+            "smallest-first" => Ok(CoinSelection::SmallestFirst),
+            "oldest-first" => Ok(CoinSelection::OldestFirst),
+            "branch-and-bound" => Ok(CoinSelection::BranchAndBound),
+            _ => Err(anyhow!("Unknown coin selection {}, expected largest-first, smallest-first, oldest-first or branch-and-bound", s)),
+        }
+    }
+}
@@ -0,0 +1,51 @@
+#[cfg(test)]
+mod tests {
+    use crate::{blockchain::{coin_selection::CoinSelection, utxo::{UnspentOutput, OutPoint, TransactionOutput}, transaction_id::TransactionId}, model::PublicKeyStr};
+
+    fn amounts(selected: Option<Vec<UnspentOutput>>) -> Vec<u64> {
+        selected.unwrap().iter().map(|utxo| utxo.amount).collect()
+    }
+
+    #[test]
+    fn strategies_select_outputs_in_their_order() {
+        let owner = PublicKeyStr::from_str("owner");
+        // oldest first
+        let txid = TransactionId::new("funding");
+        let utxos: Vec<UnspentOutput> = [5, 1, 8, 3].iter().enumerate()
+            .map(|(index, amount)| UnspentOutput::new(OutPoint::new(&txid, index as u32), &TransactionOutput::new(&owner, *amount)))
+            .collect();
+        let utxos: Vec<&UnspentOutput> = utxos.iter().collect();
+
+        assert_eq!(amounts(CoinSelection::LargestFirst.select(&utxos, 9, 0, 0)), vec![8, 5]);
+        assert_eq!(amounts(CoinSelection::SmallestFirst.select(&utxos, 9, 0, 0)), vec![1, 3, 5]);
+        assert_eq!(amounts(CoinSelection::OldestFirst.select(&utxos, 9, 0, 0)), vec![5, 1, 8]);
+        assert_eq!(amounts(CoinSelection::BranchAndBound.select(&utxos, 9, 0, 0)), vec![8, 1], "Exact match is expected");
+        // every input costs 1, so 5 and 3 cover 6 plus the cost of two inputs
+        assert_eq!(amounts(CoinSelection::BranchAndBound.select(&utxos, 6, 1, 0)), vec![5, 3], "Exact match is expected");
+        // no exact match, falls back to largest first
+        assert_eq!(amounts(CoinSelection::BranchAndBound.select(&utxos, 16, 0, 0)), vec![8, 5, 3]);
+        assert_eq!(amounts(CoinSelection::BranchAndBound.select(&utxos, 16, 0, 1)), vec![8, 5, 3], "Excess below the change cost is an exact match");
+
+        for coin_selection in [CoinSelection::LargestFirst, CoinSelection::SmallestFirst, CoinSelection::OldestFirst, CoinSelection::BranchAndBound] {
+            assert!(coin_selection.select(&utxos, 18, 0, 0).is_none(), "Outputs are not enough for {}", coin_selection);
+            assert_eq!(coin_selection.to_string().parse::<CoinSelection>().unwrap(), coin_selection);
+        }
+    }
+
+    #[test]
+    fn branch_and_bound_handles_many_outputs() {
+        // runs on a thread with a small stack, exploring must not grow it with the number of outputs
+        let selected = std::thread::Builder::new().stack_size(2 * 1024 * 1024)
+            .spawn(|| {
+                let output = TransactionOutput::new(&PublicKeyStr::from_str("owner"), 1);
+                let txid = TransactionId::new("funding");
+                let utxos: Vec<UnspentOutput> = (0..60_000).map(|index| UnspentOutput::new(OutPoint::new(&txid, index), &output)).collect();
+                let utxos: Vec<&UnspentOutput> = utxos.iter().collect();
+                CoinSelection::BranchAndBound.select(&utxos, 55_000, 0, 0).map(|selected| selected.len())
+            })
+            .unwrap()
+            .join()
+            .unwrap();
+        assert_eq!(selected, Some(55_000));
+    }
+}
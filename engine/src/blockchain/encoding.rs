@@ -0,0 +1,332 @@
+use sha1::Digest;
+use sha2::Sha256;
+
+use crate::model::PublicKeyStr;
+
+use super::{
+    asset::Issuance, balanced_transaction::BalancedTransaction, signed_balanced_transaction::{SignedBalancedTransaction, InputSignature, Witness},
+    block::BlockHeader, genesis::{Genesis, GenesisAllocation, GenesisParameters, Emission}, multisig::Multisig, script::Script, token::TokenMint, utxo::{OutPoint, TransactionOutput, TimeLock},
+};
+
+// first byte of every top level encoding, so that encodings of different types never collide
+const BALANCED_TRANSACTION_TAG: u8 = 0x01;
+const SIGNED_TRANSACTION_TAG: u8 = 0x02;
+const BLOCK_HEADER_TAG: u8 = 0x03;
+const MULTISIG_TAG: u8 = 0x04;
+const SCRIPT_TAG: u8 = 0x05;
+const ASSET_TAG: u8 = 0x06;
+const TOKEN_TAG: u8 = 0x07;
+const GENESIS_TAG: u8 = 0x08;
+
+/**
+ * Canonical encoding used for every hash and signature, it doesn't depend on any serialization library:
+ * - integers are fixed width little endian, u32 or u64
+ * - strings are the u32 length of their UTF-8 bytes followed by the bytes, hex strings are encoded as they are
+ * - lists are the u32 number of items followed by the items
+ * - optional values are 0x00 if absent, 0x01 followed by the value otherwise
+ * - enums are the number of the variant, starting at 0x01, followed by the value of the variant
+ * - fields of a struct are encoded one after another in the order they are declared,
+ *   transactions, block headers, multisig policies, scripts, asset and token ids and the genesis start with their tag byte
+ *
+ * See test-data/encoding-vectors.json for examples.
+ */
+pub trait CanonicalEncode {
+    fn encode(&self, out: &mut Vec<u8>);
+
+    fn canonical_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        self.encode(&mut out);
+        out
+    }
+
+    /**
+     * SHA-256 of the canonical bytes
+     */
+    fn canonical_hash(&self) -> Vec<u8> {
+        Sha256::digest(self.canonical_bytes()).to_vec()
+    }
+}
+
+fn put_u32(out: &mut Vec<u8>, value: u32) {
+    out.extend_from_slice(&value.to_le_bytes());
+}
+
+fn put_u64(out: &mut Vec<u8>, value: u64) {
+    out.extend_from_slice(&value.to_le_bytes());
+}
+
+fn put_str(out: &mut Vec<u8>, value: &str) {
+    put_len(out, value.len());
+    out.extend_from_slice(value.as_bytes());
+}
+
+fn put_len(out: &mut Vec<u8>, len: usize) {
+    put_u32(out, u32::try_from(len).expect("Encoded lengths fit into u32"));
+}
+
+fn put_list<T: CanonicalEncode>(out: &mut Vec<u8>, items: &[T]) {
+    put_len(out, items.len());
+    for item in items {
+        item.encode(out);
+    }
+}
+
+impl CanonicalEncode for OutPoint {
+    fn encode(&self, out: &mut Vec<u8>) {
+        put_str(out, &self.txid.0);
+        put_u32(out, self.index);
+    }
+}
+
+impl CanonicalEncode for TransactionOutput {
+    fn encode(&self, out: &mut Vec<u8>) {
+        put_str(out, &self.address.0.0);
+        put_u64(out, self.amount);
+        match self.lock {
+            Some(lock) => {
+                out.push(0x01);
+                lock.encode(out);
+            },
+            None => out.push(0x00),
+        }
+        match &self.asset {
+            Some(asset) => {
+                out.push(0x01);
+                put_str(out, &asset.0);
+            },
+            None => out.push(0x00),
+        }
+    }
+}
+
+impl CanonicalEncode for Issuance {
+    fn encode(&self, out: &mut Vec<u8>) {
+        put_str(out, &self.issuer.0.0);
+        put_str(out, &self.name);
+        put_u64(out, self.amount);
+    }
+}
+
+/**
+ * Asset ids are hashes of the issuer and the name of the asset
+ */
+pub fn asset_id_bytes(issuer: &PublicKeyStr, name: &str) -> Vec<u8> {
+    let mut out = vec![ASSET_TAG];
+    put_str(&mut out, &issuer.0.0);
+    put_str(&mut out, name);
+    out
+}
+
+/**
+ * Token ids are hashes of the first input of the minting transaction
+ */
+pub fn token_id_bytes(first_input: &OutPoint) -> Vec<u8> {
+    let mut out = vec![TOKEN_TAG];
+    first_input.encode(&mut out);
+    out
+}
+
+impl CanonicalEncode for TokenMint {
+    fn encode(&self, out: &mut Vec<u8>) {
+        put_str(out, &self.metadata);
+    }
+}
+
+impl CanonicalEncode for TimeLock {
+    fn encode(&self, out: &mut Vec<u8>) {
+        match self {
+            TimeLock::Height(height) => {
+                out.push(0x01);
+                put_u64(out, *height);
+            },
+            TimeLock::Timestamp(timestamp) => {
+                out.push(0x02);
+                put_u64(out, *timestamp);
+            },
+        }
+    }
+}
+
+impl CanonicalEncode for BalancedTransaction {
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.push(BALANCED_TRANSACTION_TAG);
+        put_str(out, &self.chain_id);
+        put_list(out, &self.inputs);
+        put_list(out, &self.outputs);
+        match self.reward_height {
+            Some(height) => {
+                out.push(0x01);
+                put_u64(out, height);
+            },
+            None => out.push(0x00),
+        }
+        match &self.issuance {
+            Some(issuance) => {
+                out.push(0x01);
+                issuance.encode(out);
+            },
+            None => out.push(0x00),
+        }
+        match &self.mint {
+            Some(mint) => {
+                out.push(0x01);
+                mint.encode(out);
+            },
+            None => out.push(0x00),
+        }
+    }
+}
+
+impl CanonicalEncode for InputSignature {
+    fn encode(&self, out: &mut Vec<u8>) {
+        put_str(out, &self.address.0.0);
+        put_str(out, &self.signature.0.0);
+    }
+}
+
+impl CanonicalEncode for SignedBalancedTransaction {
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.push(SIGNED_TRANSACTION_TAG);
+        self.balanced_transaction.encode(out);
+        put_list(out, &self.signatures);
+        self.witness.encode(out);
+    }
+}
+
+impl CanonicalEncode for Witness {
+    fn encode(&self, out: &mut Vec<u8>) {
+        put_list(out, &self.multisigs);
+        put_list(out, &self.scripts);
+        put_len(out, self.preimages.len());
+        for preimage in &self.preimages {
+            put_str(out, preimage);
+        }
+    }
+}
+
+impl CanonicalEncode for Multisig {
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.push(MULTISIG_TAG);
+        put_u32(out, self.threshold);
+        put_len(out, self.keys.len());
+        for key in &self.keys {
+            put_str(out, &key.0.0);
+        }
+    }
+}
+
+impl CanonicalEncode for Script {
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.push(SCRIPT_TAG);
+        encode_script(self, out);
+    }
+}
+
+// nested scripts have no tag
+fn encode_script(script: &Script, out: &mut Vec<u8>) {
+    let encode_scripts = |scripts: &[Script], out: &mut Vec<u8>| {
+        put_len(out, scripts.len());
+        for script in scripts {
+            encode_script(script, out);
+        }
+    };
+    match script {
+        Script::Signature(key) => {
+            out.push(0x01);
+            put_str(out, &key.0.0);
+        },
+        Script::HashPreimage(hash) => {
+            out.push(0x02);
+            put_str(out, hash);
+        },
+        Script::After(lock) => {
+            out.push(0x03);
+            lock.encode(out);
+        },
+        Script::And(scripts) => {
+            out.push(0x04);
+            encode_scripts(scripts, out);
+        },
+        Script::Or(scripts) => {
+            out.push(0x05);
+            encode_scripts(scripts, out);
+        },
+        Script::Threshold { threshold, scripts } => {
+            out.push(0x06);
+            put_u32(out, *threshold);
+            encode_scripts(scripts, out);
+        },
+        Script::Before(lock) => {
+            out.push(0x07);
+            lock.encode(out);
+        },
+    }
+}
+
+impl CanonicalEncode for BlockHeader {
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.push(BLOCK_HEADER_TAG);
+        put_u64(out, self.height);
+        put_str(out, &self.prev_hash);
+        put_u64(out, self.timestamp);
+        put_str(out, &self.proposer.0.0);
+        put_str(out, &self.transactions_root);
+    }
+}
+
+impl CanonicalEncode for Genesis {
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.push(GENESIS_TAG);
+        put_str(out, &self.chain_id);
+        put_list(out, &self.allocations);
+        put_len(out, self.validators.len());
+        for validator in &self.validators {
+            put_str(out, &validator.0.0);
+        }
+        self.parameters.encode(out);
+    }
+}
+
+impl CanonicalEncode for GenesisAllocation {
+    fn encode(&self, out: &mut Vec<u8>) {
+        put_str(out, &self.address.0.0);
+        put_u64(out, self.amount);
+        match self.lock {
+            Some(lock) => {
+                out.push(0x01);
+                lock.encode(out);
+            },
+            None => out.push(0x00),
+        }
+    }
+}
+
+impl CanonicalEncode for GenesisParameters {
+    fn encode(&self, out: &mut Vec<u8>) {
+        put_u64(out, self.timestamp);
+        put_u64(out, u64::try_from(self.max_block_transactions).expect("Block size limit fits into u64"));
+        match &self.emission {
+            Some(emission) => {
+                out.push(0x01);
+                emission.encode(out);
+            },
+            None => out.push(0x00),
+        }
+    }
+}
+
+impl CanonicalEncode for Emission {
+    fn encode(&self, out: &mut Vec<u8>) {
+        match self {
+            Emission::Fixed { subsidy } => {
+                out.push(0x01);
+                put_u64(out, *subsidy);
+            },
+            Emission::Halving { initial_subsidy, interval } => {
+                out.push(0x02);
+                put_u64(out, *initial_subsidy);
+                put_u64(out, *interval);
+            },
+        }
+    }
+}
@@ -0,0 +1,42 @@
+#[cfg(test)]
+mod tests {
+    use std::{path::Path, fs};
+
+    use rsa::RsaPrivateKey;
+    use serde_json::Value;
+
+    use crate::{blockchain::{balanced_transaction::BalancedTransaction, signed_balanced_transaction::SignedBalancedTransaction, block::BlockHeader, encoding::CanonicalEncode, genesis::Genesis, merkle::merkle_root, utxo::{UnspentOutput, TransactionOutput}}, model::PrivateKeyStr};
+
+    #[test]
+    fn encodings_match_published_test_vectors() {
+        let test_data = Path::new(env!("CARGO_MANIFEST_DIR")).join("../test-data");
+        let vectors: Value = serde_json::from_str(&fs::read_to_string(test_data.join("encoding-vectors.json")).unwrap()).unwrap();
+        let expected = |name: &str| vectors[name].as_str().unwrap().to_owned();
+
+        let transaction: BalancedTransaction = serde_json::from_value(vectors["balanced_transaction"].clone()).unwrap();
+        assert_eq!(hex::encode(transaction.canonical_bytes()), expected("balanced_transaction_encoding"), "Transaction encoding is wrong");
+
+        // signatures are deterministic, so a wallet signing the same transaction produces the same signature
+        let private_key = fs::read_to_string(test_data.join("root_private_key")).unwrap();
+        let private_key = RsaPrivateKey::try_from(&PrivateKeyStr(private_key.trim().to_owned())).unwrap();
+        let signed_transaction: SignedBalancedTransaction = serde_json::from_value(vectors["signed_transaction"].clone()).unwrap();
+        assert_eq!(transaction.sign(&private_key).unwrap().signatures, signed_transaction.signatures, "Signature is wrong");
+        let signer = &signed_transaction.signatures[0].address;
+        let spent = UnspentOutput::new(transaction.inputs[0].clone(), &TransactionOutput::new(signer, 100));
+        signed_transaction.verify_signatures(&[spent]).unwrap();
+
+        assert_eq!(hex::encode(signed_transaction.canonical_bytes()), expected("signed_transaction_encoding"), "Signed transaction encoding is wrong");
+        assert_eq!(signed_transaction.id().0, expected("txid"), "Transaction id is wrong");
+        assert_eq!(transaction.id(), signed_transaction.id(), "Signing must not change the transaction id");
+        assert_eq!(hex::encode(signed_transaction.witness_hash()), expected("witness_hash"), "Witness hash is wrong");
+
+        let header: BlockHeader = serde_json::from_value(vectors["block_header"].clone()).unwrap();
+        assert_eq!(header.transactions_root, hex::encode(merkle_root(&[signed_transaction.witness_hash()]).unwrap()), "Transactions root is wrong");
+        assert_eq!(hex::encode(header.canonical_bytes()), expected("block_header_encoding"), "Block header encoding is wrong");
+        assert_eq!(hex::encode(header.hash()), expected("block_hash"), "Block hash is wrong");
+
+        let genesis: Genesis = serde_json::from_value(vectors["genesis"].clone()).unwrap();
+        assert_eq!(hex::encode(genesis.canonical_bytes()), expected("genesis_encoding"), "Genesis encoding is wrong");
+        assert_eq!(genesis.hash_str(), expected("genesis_hash"), "Genesis hash is wrong");
+    }
+}
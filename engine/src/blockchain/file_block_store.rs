@@ -0,0 +1,137 @@
+use std::{fs::{File, OpenOptions, self}, path::{Path, PathBuf}, io::{Read, Write, ErrorKind, BufReader}};
+
+use anyhow::{Result, anyhow};
+use log::{warn, info};
+use serde::{Serialize, Deserialize};
+
+use super::{block::Block, validator_signature::ValidatorSignature, block_store::BlockStore};
+
+const LOG_FILE_NAME: &str = "blocks.log";
+
+/**
+ * A single entry of the block log.
+ * Blocks are never rewritten, signatures collected after a block has been written are appended as separate records.
+ */
+#[derive(Serialize, Deserialize)]
+enum LogRecord {
+    Block(Block),
+    ValidatorSignature { block_hash: String, signature: ValidatorSignature },
+}
+
+/**
+ * Keeps blocks in an append-only log on the disk, and a copy of them in memory for reads.
+ * Every record is serialized into CBOR and prefixed with its length (8 bytes, big endian).
+ */
+pub struct FileBlockStore {
+    path: PathBuf,
+    file: File,
+    blocks: Vec<Block>,
+}
+
+impl FileBlockStore {
+    /**
+     * Opens (or creates) block log in the given directory and reads all blocks written so far.
+     * A partially written record at the end of the log (e.g. node was killed in the middle of a write) is discarded.
+     */
+    pub fn open(data_dir: &Path) -> Result<FileBlockStore> {
+        fs::create_dir_all(data_dir)?;
+        let path = data_dir.join(LOG_FILE_NAME);
+        let file = OpenOptions::new().create(true).read(true).append(true).open(&path)?;
+
+        let (records, valid_length) = Self::read_records(&file)?;
+        let file_length = file.metadata()?.len();
+        if valid_length < file_length {
+            warn!("Block log {} has an incomplete record at the end, {} bytes will be discarded", path.display(), file_length - valid_length);
+            file.set_len(valid_length)?;
+        }
+
+        let mut blocks: Vec<Block> = Vec::new();
+        for record in records {
+            match record {
+                LogRecord::Block(block) => blocks.push(block),
+                LogRecord::ValidatorSignature { block_hash, signature } => {
+                    let block = blocks.iter_mut().rev().find(|block| block.hash == block_hash)
+                        .ok_or(anyhow!("Block log refers to unknown block {}", block_hash))?;
+                    block.add_validator_signature(signature);
+                },
+            }
+        }
+
+        info!("Block log {} opened, {} blocks found", path.display(), blocks.len());
+        Ok(FileBlockStore { path, file, blocks })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn write_record(&mut self, record: &LogRecord) -> Result<()> {
+        let bytes = serde_cbor::to_vec(record)?;
+        let mut buf = Vec::with_capacity(bytes.len() + 8);
+        buf.extend_from_slice(&(bytes.len() as u64).to_be_bytes());
+        buf.extend_from_slice(&bytes);
+
+        self.file.write_all(&buf)?;
+        self.file.sync_data()?;
+        Ok(())
+    }
+
+    /**
+     * Returns all complete records and the number of bytes they occupy in the file
+     */
+    fn read_records(file: &File) -> Result<(Vec<LogRecord>, u64)> {
+        let mut reader = BufReader::new(file);
+        let mut records = Vec::new();
+        let mut valid_length = 0_u64;
+
+        loop {
+            let mut len: [u8; 8] = [0; 8];
+            match reader.read_exact(&mut len) {
+                Ok(_) => (),
+                Err(err) if err.kind() == ErrorKind::UnexpectedEof => break,
+                Err(err) => return Err(err.into()),
+            }
+
+            let size = u64::from_be_bytes(len);
+            let mut buf = Vec::new();
+            let read = reader.by_ref().take(size).read_to_end(&mut buf)?;
+            if (read as u64) < size {
+                break;
+            }
+
+            let record: LogRecord = serde_cbor::from_slice(&buf)
+                .map_err(|err| anyhow!("Block log record at offset {} is corrupted: {}", valid_length, err))?;
+            records.push(record);
+            valid_length += 8 + size;
+        }
+
+        Ok((records, valid_length))
+    }
+}
+
+impl BlockStore for FileBlockStore {
+    fn len(&self) -> usize {
+        self.blocks.len()
+    }
+
+    fn get(&self, index: usize) -> Option<&Block> {
+        self.blocks.get(index)
+    }
+
+    fn append(&mut self, block: Block) -> Result<()> {
+        self.write_record(&LogRecord::Block(block.clone()))?;
+        self.blocks.push(block);
+        Ok(())
+    }
+
+    fn add_validator_signature(&mut self, index: usize, signature: ValidatorSignature) -> Result<bool> {
+        let block = self.blocks.get(index).ok_or(anyhow!("Block with index {} not found", index))?;
+        if block.validator_signatures().contains(&signature) {
+            return Ok(false);
+        }
+
+        let block_hash = block.hash.to_owned();
+        self.write_record(&LogRecord::ValidatorSignature { block_hash, signature: signature.clone() })?;
+        Ok(self.blocks[index].add_validator_signature(signature))
+    }
+}
@@ -0,0 +1,195 @@
+use std::{path::Path, fs, collections::HashSet};
+
+use anyhow::{Result, anyhow};
+use serde::{Serialize, Deserialize};
+
+use crate::model::PublicKeyStr;
+
+use super::{encoding::CanonicalEncode, utxo::{UnspentOutput, OutPoint, TimeLock}, transaction_id::TransactionId};
+
+/**
+ * Initial state of the blockchain, every node of the network must start from the same genesis
+ */
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Genesis {
+    pub chain_id: String,
+    pub allocations: Vec<GenesisAllocation>,
+    /**
+     * Validators trusted from the start, more validators can join later
+     */
+    #[serde(default)]
+    pub validators: Vec<PublicKeyStr>,
+    #[serde(default)]
+    pub parameters: GenesisParameters,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct GenesisAllocation {
+    pub address: PublicKeyStr,
+    pub amount: u64,
+    /**
+     * Vesting, the allocation can't be spent until the lock expires
+     */
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lock: Option<TimeLock>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct GenesisParameters {
+    /**
+     * Milliseconds since unix epoch, timestamps of all blocks must not be earlier
+     */
+    #[serde(default)]
+    pub timestamp: u64,
+    #[serde(default = "default_max_block_transactions")]
+    pub max_block_transactions: usize,
+    /**
+     * Coins created by the reward of every block on top of the fees, without it proposers earn the fees only
+     */
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub emission: Option<Emission>,
+}
+
+/**
+ * Schedule of the subsidy the proposer of a block may claim in addition to the fees
+ */
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub enum Emission {
+    /**
+     * Every block gets the same subsidy
+     */
+    Fixed { subsidy: u64 },
+    /**
+     * Subsidy starts at `initial_subsidy` and halves every `interval` blocks, until it drops to zero
+     */
+    Halving { initial_subsidy: u64, interval: u64 },
+}
+
+fn default_max_block_transactions() -> usize {
+    1000
+}
+
+impl Default for GenesisParameters {
+    fn default() -> Self {
+        GenesisParameters { timestamp: 0, max_block_transactions: default_max_block_transactions(), emission: None }
+    }
+}
+
+impl Emission {
+    /**
+     * Subsidy of the block at the height, the first block is at height 1
+     */
+    pub fn subsidy(&self, height: u64) -> u64 {
+        match self {
+            Emission::Fixed { subsidy } => *subsidy,
+            Emission::Halving { initial_subsidy, interval } => {
+                let halvings = height.saturating_sub(1) / (*interval).max(1);
+                initial_subsidy.checked_shr(u32::try_from(halvings).unwrap_or(u32::MAX)).unwrap_or(0)
+            },
+        }
+    }
+
+    /**
+     * Most coins the schedule creates, None if they don't fit into u64.
+     * A fixed subsidy never stops, so only the subsidy of a single block is counted, it's capped once the maximum supply is issued.
+     */
+    pub fn max_emission(&self) -> Option<u64> {
+        match self {
+            Emission::Fixed { subsidy } => Some(*subsidy),
+            Emission::Halving { initial_subsidy, interval } => {
+                let mut total = 0_u64;
+                let mut subsidy = *initial_subsidy;
+                while subsidy > 0 {
+                    total = total.checked_add(subsidy.checked_mul(*interval)?)?;
+                    subsidy >>= 1;
+                }
+                Some(total)
+            },
+        }
+    }
+}
+
+impl GenesisAllocation {
+    pub fn new(address: &PublicKeyStr, amount: u64) -> Self {
+        GenesisAllocation { address: address.clone(), amount, lock: None }
+    }
+}
+
+impl Genesis {
+    pub fn new(chain_id: &str, allocations: Vec<GenesisAllocation>, validators: Vec<PublicKeyStr>) -> Self {
+        Genesis { chain_id: chain_id.to_owned(), allocations, validators, parameters: GenesisParameters::default() }
+    }
+
+    /**
+     * Reads genesis from a JSON file
+     */
+    pub fn load(path: &Path) -> Result<Genesis> {
+        let json = fs::read_to_string(path).map_err(|err| anyhow!("Unable to read genesis file {}: {}", path.display(), err))?;
+        let genesis: Genesis = serde_json::from_str(&json).map_err(|err| anyhow!("Genesis file {} is malformed: {}", path.display(), err))?;
+        genesis.validate()?;
+        Ok(genesis)
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        if self.chain_id.is_empty() {
+            return Err(anyhow!("Genesis chain id must not be empty"));
+        }
+        if self.allocations.is_empty() {
+            return Err(anyhow!("Genesis must have at least one allocation"));
+        }
+        if let Some(allocation) = self.allocations.iter().find(|allocation| allocation.amount == 0) {
+            return Err(anyhow!("Genesis allocation to {} must not be empty", allocation.address));
+        }
+        let allocated = self.allocations.iter().try_fold(0_u64, |total, allocation| total.checked_add(allocation.amount))
+            .ok_or(anyhow!("Total amount of genesis allocations is too large"))?;
+
+        let mut validators = HashSet::new();
+        if let Some(validator) = self.validators.iter().find(|validator| !validators.insert(*validator)) {
+            return Err(anyhow!("Genesis validator {} is listed more than once", validator));
+        }
+        if self.parameters.max_block_transactions == 0 {
+            return Err(anyhow!("Genesis parameter max_block_transactions must be positive"));
+        }
+        if let Some(Emission::Halving { interval: 0, .. }) = self.parameters.emission {
+            return Err(anyhow!("Genesis emission halving interval must be positive"));
+        }
+        if let Some(emission) = &self.parameters.emission {
+            emission.max_emission().and_then(|emitted| allocated.checked_add(emitted))
+                .ok_or(anyhow!("Genesis allocations and emission exceed the maximum supply of {}", u64::MAX))?;
+        }
+        Ok(())
+    }
+
+    /**
+     * Subsidy the reward of the block at the height may claim on top of the fees
+     */
+    pub fn subsidy(&self, height: u64) -> u64 {
+        self.parameters.emission.as_ref().map_or(0, |emission| emission.subsidy(height))
+    }
+
+    /**
+     * Unspent outputs created by the allocations, in the order they are listed.
+     * They are identified by the hash of the genesis and the index of the allocation.
+     */
+    pub fn initial_utxos(&self) -> Vec<UnspentOutput> {
+        let txid = TransactionId(self.hash_str());
+        self.allocations.iter().enumerate().map(|(idx, allocation)| UnspentOutput {
+            outpoint: OutPoint::new(&txid, idx as u32),
+            address: allocation.address.clone(),
+            amount: allocation.amount,
+            lock: allocation.lock,
+            asset: None,
+        }).collect()
+    }
+
+    /**
+     * Hash of the genesis is the hash of the blockchain before the first block, it's the hash of the canonical encoding
+     */
+    pub fn hash(&self) -> Vec<u8> {
+        self.canonical_hash()
+    }
+
+    pub fn hash_str(&self) -> String {
+        hex::encode(self.hash())
+    }
+}
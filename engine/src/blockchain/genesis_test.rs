@@ -0,0 +1,40 @@
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use crate::{blockchain::{blockchain::BlockChain, genesis::{Genesis, GenesisAllocation}}, encryption::generate_rsa_keypair_custom};
+
+    #[test]
+    fn genesis_is_loaded_from_file() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("../test-data/genesis.json");
+        let genesis = Genesis::load(&path).unwrap();
+        assert_eq!(genesis.chain_id, "local-testnet", "Chain id is wrong");
+        assert_eq!(genesis.validators.len(), 4, "Number of validators is wrong");
+        assert_eq!((genesis.subsidy(1000), genesis.subsidy(1001)), (50, 25), "Emission schedule is wrong");
+
+        let (_, validator_public_key) = &generate_rsa_keypair_custom().unwrap();
+        let blockchain = BlockChain::new(validator_public_key, genesis.clone());
+        assert_eq!(blockchain.all_balances().unwrap(), vec![(genesis.allocations[0].address.clone(), 100)], "Balances are wrong");
+        assert_eq!(blockchain.blockchain_hash(), genesis.hash_str(), "Hash of an empty blockchain is wrong");
+    }
+
+    #[test]
+    fn every_allocation_is_spendable() {
+        let (_, validator_public_key) = &generate_rsa_keypair_custom().unwrap();
+        let (_, pub_1) = &generate_rsa_keypair_custom().unwrap();
+        let (_, pub_2) = &generate_rsa_keypair_custom().unwrap();
+
+        let genesis = Genesis::new("test", vec![GenesisAllocation::new(pub_1, 10), GenesisAllocation::new(pub_2, 5), GenesisAllocation::new(pub_1, 1)], Vec::new());
+        genesis.validate().unwrap();
+        let blockchain = BlockChain::new(validator_public_key, genesis);
+        assert_eq!(blockchain.balance(pub_1).unwrap().amount, 11, "Balance is wrong");
+        assert_eq!(blockchain.balance(pub_1).unwrap().utxos, 2, "Number of utxos is wrong");
+        assert_eq!(blockchain.balance(pub_2).unwrap().amount, 5, "Balance is wrong");
+
+        let err = Genesis::new("test", vec![GenesisAllocation::new(pub_1, 0)], Vec::new()).validate().unwrap_err();
+        assert!(err.to_string().contains("must not be empty"), "Error is wrong: {}", err);
+        let err = Genesis::new("test", vec![GenesisAllocation::new(pub_1, 1)], vec![pub_2.clone(), pub_2.clone()]).validate().unwrap_err();
+        assert!(err.to_string().contains("more than once"), "Error is wrong: {}", err);
+        assert!(Genesis::new("", vec![GenesisAllocation::new(pub_1, 1)], Vec::new()).validate().is_err(), "Chain id is required");
+    }
+}
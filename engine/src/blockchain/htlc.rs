@@ -0,0 +1,82 @@
+use std::{fs, path::Path};
+
+use anyhow::{Result, anyhow};
+use serde::{Serialize, Deserialize};
+use sha1::Digest;
+use sha2::Sha256;
+
+use crate::model::PublicKeyStr;
+
+use super::{script::Script, utxo::TimeLock, signed_balanced_transaction::Witness};
+
+/**
+ * Hash time-locked contract: the recipient claims the funds with a preimage of the hash before the deadline,
+ * afterwards the sender gets them back. Funds are held by the address of the contract script.
+ */
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Htlc {
+    pub sender: PublicKeyStr,
+    pub recipient: PublicKeyStr,
+    /**
+     * Hex encoded SHA-256 hash of the secret
+     */
+    pub hash: String,
+    pub deadline: TimeLock,
+}
+
+impl Htlc {
+    pub fn new(sender: &PublicKeyStr, recipient: &PublicKeyStr, hash: &str, deadline: TimeLock) -> Result<Htlc> {
+        let htlc = Htlc { sender: sender.clone(), recipient: recipient.clone(), hash: hash.to_lowercase(), deadline };
+        htlc.script().validate()?;
+        Ok(htlc)
+    }
+
+    /**
+     * Reads contract from a JSON file
+     */
+    pub fn load(path: &Path) -> Result<Htlc> {
+        let json = fs::read_to_string(path).map_err(|err| anyhow!("Unable to read contract file {}: {}", path.display(), err))?;
+        let htlc: Htlc = serde_json::from_str(&json).map_err(|err| anyhow!("Contract file {} is malformed: {}", path.display(), err))?;
+        htlc.script().validate()?;
+        Ok(htlc)
+    }
+
+    /**
+     * Hex encoded hash of the hex encoded secret
+     */
+    pub fn hash_of(preimage: &str) -> Result<String> {
+        let bytes = hex::decode(preimage).map_err(|err| anyhow!("Preimage {} is not hex encoded: {}", preimage, err))?;
+        Ok(hex::encode(Sha256::digest(bytes)))
+    }
+
+    pub fn script(&self) -> Script {
+        Script::Or(vec![
+            Script::And(vec![Script::Signature(self.recipient.clone()), Script::HashPreimage(self.hash.clone()), Script::Before(self.deadline)]),
+            Script::And(vec![Script::Signature(self.sender.clone()), Script::After(self.deadline)]),
+        ])
+    }
+
+    /**
+     * Address the funds of the contract are sent to
+     */
+    pub fn address(&self) -> PublicKeyStr {
+        self.script().address()
+    }
+
+    /**
+     * Witness of a claim by the recipient, the preimage is hex encoded
+     */
+    pub fn claim_witness(&self, preimage: &str) -> Result<Witness> {
+        if Self::hash_of(preimage)? != self.hash {
+            return Err(anyhow!("Preimage does not match hash {} of the contract", self.hash));
+        }
+        Ok(Witness { scripts: vec![self.script()], preimages: vec![preimage.to_owned()], ..Witness::default() })
+    }
+
+    /**
+     * Witness of a refund to the sender
+     */
+    pub fn refund_witness(&self) -> Witness {
+        Witness { scripts: vec![self.script()], ..Witness::default() }
+    }
+}
@@ -0,0 +1,45 @@
+#[cfg(test)]
+mod tests {
+    use rsa::RsaPrivateKey;
+
+    use crate::{blockchain::{transaction::Transaction, coin_selection::CoinSelection, blockchain::BlockChain, genesis::{Genesis, GenesisAllocation}, htlc::Htlc, utxo::TimeLock, signed_balanced_transaction::Witness}, encryption::generate_rsa_keypair_custom, model::{PublicKeyStr, PrivateKeyStr}};
+
+    fn send(blockchain: &mut BlockChain, from: &PublicKeyStr, to: &PublicKeyStr, amount: u64, private_key: &PrivateKeyStr, witness: &Witness, validator_private_key: &PrivateKeyStr) -> anyhow::Result<()> {
+        let private_key: RsaPrivateKey = private_key.try_into()?;
+        Transaction::new(from, to, amount)
+            .balance_transaction(blockchain, 0, CoinSelection::default())?
+            .sign(&private_key)?
+            .with_witness(witness.clone())
+            .commit(blockchain, validator_private_key)?;
+        Ok(())
+    }
+
+    #[test]
+    fn htlc_is_claimed_before_deadline_and_refunded_after_it() {
+        let (validator_private_key, validator_public_key) = &generate_rsa_keypair_custom().unwrap();
+        let (sender_private_key, sender) = &generate_rsa_keypair_custom().unwrap();
+        let (recipient_private_key, recipient) = &generate_rsa_keypair_custom().unwrap();
+        let mut blockchain = BlockChain::new(validator_public_key, Genesis::new("test", vec![GenesisAllocation::new(sender, 10)], Vec::new()));
+
+        let (secret, other_secret) = (hex::encode("secret"), hex::encode("other secret"));
+        let claimed = Htlc::new(sender, recipient, &Htlc::hash_of(&secret).unwrap(), TimeLock::Height(4)).unwrap();
+        let refunded = Htlc::new(sender, recipient, &Htlc::hash_of(&other_secret).unwrap(), TimeLock::Height(4)).unwrap();
+        assert_ne!(claimed.address(), refunded.address(), "Contracts with different hashes must have different addresses");
+        send(&mut blockchain, sender, &claimed.address(), 3, sender_private_key, &Witness::default(), validator_private_key).unwrap();
+        send(&mut blockchain, sender, &refunded.address(), 3, sender_private_key, &Witness::default(), validator_private_key).unwrap();
+
+        // the next block is at height 3, before the deadline
+        assert!(claimed.claim_witness(&other_secret).is_err(), "Wrong preimage must be rejected");
+        let err = send(&mut blockchain, &claimed.address(), sender, 3, sender_private_key, &claimed.refund_witness(), validator_private_key).unwrap_err();
+        assert!(err.to_string().contains("are not met"), "Refund before the deadline must be rejected: {}", err);
+        send(&mut blockchain, &claimed.address(), recipient, 3, recipient_private_key, &claimed.claim_witness(&secret).unwrap(), validator_private_key).unwrap();
+        assert_eq!(blockchain.balance(recipient).unwrap().amount, 3, "Balance of the recipient is wrong");
+
+        // the next block is at the deadline
+        let err = send(&mut blockchain, &refunded.address(), recipient, 3, recipient_private_key, &refunded.claim_witness(&other_secret).unwrap(), validator_private_key).unwrap_err();
+        assert!(err.to_string().contains("are not met"), "Claim after the deadline must be rejected: {}", err);
+        send(&mut blockchain, &refunded.address(), sender, 3, sender_private_key, &refunded.refund_witness(), validator_private_key).unwrap();
+        assert_eq!(blockchain.balance(sender).unwrap().amount, 7, "Balance of the sender is wrong");
+        assert_eq!(blockchain.audit(), Ok(()));
+    }
+}
@@ -0,0 +1,30 @@
+use anyhow::{Result, anyhow};
+use sha1::Digest;
+use sha2::Sha256;
+
+/**
+ * Computes Merkle root of the given leaf hashes.
+ * Pairs of hashes are hashed together level by level, the last hash of a level with odd length is carried up unhashed.
+ * Pairing it with itself would give lists ending with a duplicated hash the same root (CVE-2012-2459).
+ */
+pub fn merkle_root(leaves: &[Vec<u8>]) -> Result<Vec<u8>> {
+    if leaves.is_empty() {
+        return Err(anyhow!("Merkle root of an empty list can't be computed"));
+    }
+
+    let mut level: Vec<Vec<u8>> = leaves.to_vec();
+    while level.len() > 1 {
+        level = level.chunks(2).map(|pair| match pair {
+            [left, right] => {
+                let mut hasher = Sha256::new();
+                hasher.update(left);
+                hasher.update(right);
+                hasher.finalize().to_vec()
+            },
+            [odd] => odd.clone(),
+            _ => unreachable!(),
+        }).collect();
+    }
+
+    Ok(level.remove(0))
+}
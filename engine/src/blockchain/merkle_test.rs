@@ -0,0 +1,26 @@
+#[cfg(test)]
+mod tests {
+    use sha1::Digest;
+    use sha2::Sha256;
+
+    use crate::blockchain::merkle::merkle_root;
+
+    fn hash(left: &[u8], right: &[u8]) -> Vec<u8> {
+        let mut hasher = Sha256::new();
+        hasher.update(left);
+        hasher.update(right);
+        hasher.finalize().to_vec()
+    }
+
+    #[test]
+    fn merkle_root_of_leaves() {
+        let (a, b, c) = (vec![1_u8], vec![2_u8], vec![3_u8]);
+
+        assert!(merkle_root(&[]).is_err(), "Empty list has no merkle root");
+        assert_eq!(merkle_root(std::slice::from_ref(&a)).unwrap(), a, "Single leaf is the root itself");
+        assert_eq!(merkle_root(&[a.clone(), b.clone()]).unwrap(), hash(&a, &b));
+        assert_eq!(merkle_root(&[a.clone(), b.clone(), c.clone()]).unwrap(), hash(&hash(&a, &b), &c), "Odd leaf must be carried up");
+        assert_ne!(merkle_root(&[a.clone(), b.clone(), c.clone()]).unwrap(), merkle_root(&[a.clone(), b.clone(), c.clone(), c]).unwrap(), "Duplicated last leaf must change the root");
+        assert_ne!(merkle_root(&[b.clone(), a.clone()]).unwrap(), merkle_root(&[a, b]).unwrap(), "Order of leaves matters");
+    }
+}
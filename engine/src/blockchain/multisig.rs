@@ -0,0 +1,65 @@
+use std::{collections::HashSet, fs, path::Path};
+
+use anyhow::{Result, anyhow};
+use serde::{Serialize, Deserialize};
+
+use crate::model::{PublicKeyStr, HexString};
+
+use super::encoding::CanonicalEncode;
+
+// addresses of multisigs start with it, so they can't be confused with public keys
+const MULTISIG_ADDRESS_PREFIX: &str = "multisig-";
+const MAX_MULTISIG_KEYS: usize = 16;
+
+/**
+ * Address owned by several keys, any `threshold` of them can spend its outputs.
+ * The address is the hash of the policy, the policy is revealed by transactions spending from it.
+ */
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Multisig {
+    pub threshold: u32,
+    pub keys: Vec<PublicKeyStr>,
+}
+
+impl Multisig {
+    pub fn new(threshold: u32, keys: &[PublicKeyStr]) -> Result<Multisig> {
+        let multisig = Multisig { threshold, keys: keys.to_vec() };
+        multisig.validate()?;
+        Ok(multisig)
+    }
+
+    /**
+     * Reads policy from a JSON file
+     */
+    pub fn load(path: &Path) -> Result<Multisig> {
+        let json = fs::read_to_string(path).map_err(|err| anyhow!("Unable to read multisig file {}: {}", path.display(), err))?;
+        let multisig: Multisig = serde_json::from_str(&json).map_err(|err| anyhow!("Multisig file {} is malformed: {}", path.display(), err))?;
+        multisig.validate()?;
+        Ok(multisig)
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        if self.keys.is_empty() || self.keys.len() > MAX_MULTISIG_KEYS {
+            return Err(anyhow!("Multisig must have between 1 and {} keys, {} given", MAX_MULTISIG_KEYS, self.keys.len()));
+        }
+        if self.threshold == 0 || self.threshold as usize > self.keys.len() {
+            return Err(anyhow!("Multisig threshold must be between 1 and {}, {} given", self.keys.len(), self.threshold));
+        }
+        let mut seen = HashSet::new();
+        if let Some(key) = self.keys.iter().find(|key| !seen.insert(*key)) {
+            return Err(anyhow!("Multisig key {} is listed more than once", key));
+        }
+        Ok(())
+    }
+
+    /**
+     * Order of the keys matters, the same keys in another order make another address
+     */
+    pub fn address(&self) -> PublicKeyStr {
+        PublicKeyStr(HexString(format!("{}{}", MULTISIG_ADDRESS_PREFIX, hex::encode(self.canonical_hash()))))
+    }
+
+    pub fn is_multisig_address(address: &PublicKeyStr) -> bool {
+        address.0.0.starts_with(MULTISIG_ADDRESS_PREFIX)
+    }
+}
@@ -0,0 +1,47 @@
+#[cfg(test)]
+mod tests {
+    use rsa::RsaPrivateKey;
+
+    use crate::{blockchain::{transaction::Transaction, coin_selection::CoinSelection, blockchain::BlockChain, genesis::{Genesis, GenesisAllocation}, multisig::Multisig, signed_balanced_transaction::Witness}, encryption::generate_rsa_keypair_custom};
+
+    #[test]
+    fn multisig_outputs_are_spent_with_threshold_signatures() {
+        let (validator_private_key, validator_public_key) = &generate_rsa_keypair_custom().unwrap();
+        let (priv_1, pub_1) = &generate_rsa_keypair_custom().unwrap();
+        let (_, pub_2) = &generate_rsa_keypair_custom().unwrap();
+        let keys: Vec<_> = (0..3).map(|_| generate_rsa_keypair_custom().unwrap()).collect();
+        let private_keys: Vec<RsaPrivateKey> = keys.iter().map(|(private_key, _)| private_key.try_into().unwrap()).collect();
+        let public_keys: Vec<_> = keys.iter().map(|(_, public_key)| public_key.clone()).collect();
+
+        assert!(Multisig::new(4, &public_keys).is_err(), "Threshold can't exceed number of keys");
+        let multisig = Multisig::new(2, &public_keys).unwrap();
+        let treasury = &multisig.address();
+        assert!(Multisig::is_multisig_address(treasury), "Address is wrong: {}", treasury);
+
+        let mut blockchain = BlockChain::new(validator_public_key, Genesis::new("test", vec![GenesisAllocation::new(pub_1, 10)], Vec::new()));
+        Transaction::new(pub_1, treasury, 6)
+            .balance_transaction(&blockchain, 0, CoinSelection::default()).unwrap()
+            .sign(&priv_1.try_into().unwrap()).unwrap()
+            .commit(&mut blockchain, validator_private_key).unwrap();
+        assert_eq!(blockchain.balance(treasury).unwrap().amount, 6, "Balance of the multisig is wrong");
+
+        let witness = Witness { multisigs: vec![multisig.clone()], ..Witness::default() };
+        let transaction = Transaction::new(treasury, pub_2, 5).balance_transaction(&blockchain, 0, CoinSelection::default()).unwrap();
+
+        let err = transaction.sign_with(&private_keys[..2]).unwrap().commit(&mut blockchain, validator_private_key).unwrap_err();
+        assert!(err.to_string().contains("is not revealed"), "Error is wrong: {}", err);
+
+        let err = transaction.sign(&private_keys[1]).unwrap().with_witness(witness.clone()).commit(&mut blockchain, validator_private_key).unwrap_err();
+        assert!(err.to_string().contains("needs 2 signatures, 1 given"), "Error is wrong: {}", err);
+
+        let err = transaction.sign_with(&[private_keys[0].clone(), priv_1.try_into().unwrap()]).unwrap().with_witness(witness.clone()).commit(&mut blockchain, validator_private_key).unwrap_err();
+        assert!(err.to_string().contains("owns none of its inputs"), "Error is wrong: {}", err);
+
+        transaction.sign_with(&[private_keys[0].clone(), private_keys[2].clone()]).unwrap()
+            .with_witness(witness.clone())
+            .commit(&mut blockchain, validator_private_key).unwrap();
+        assert_eq!(blockchain.balance(pub_2).unwrap().amount, 5, "Balance of the recipient is wrong");
+        assert_eq!(blockchain.balance(treasury).unwrap().amount, 1, "Change must go back to the multisig");
+        assert_eq!(blockchain.audit(), Ok(()));
+    }
+}
@@ -0,0 +1,168 @@
+use std::{collections::HashSet, fs, path::Path};
+
+use anyhow::{Result, anyhow};
+use serde::{Serialize, Deserialize};
+use sha1::Digest;
+use sha2::Sha256;
+
+use crate::model::{PublicKeyStr, HexString};
+
+use super::{encoding::CanonicalEncode, utxo::{TimeLock, BlockTime}};
+
+// addresses of scripts start with it, so they can't be confused with public keys
+const SCRIPT_ADDRESS_PREFIX: &str = "script-";
+// scripts are rejected before evaluation if they exceed any of the limits
+const MAX_SCRIPT_DEPTH: usize = 8;
+const MAX_SCRIPT_COST: u64 = 100;
+const SIGNATURE_COST: u64 = 5;
+const HASH_PREIMAGE_COST: u64 = 2;
+const OPERATOR_COST: u64 = 1;
+// limits of the preimages revealed by a transaction
+const MAX_PREIMAGES: usize = 16;
+const MAX_PREIMAGE_BYTES: usize = 64;
+
+/**
+ * Condition an output of a script address is spent under.
+ * Scripts are predicates without loops, so their cost is known before they are evaluated.
+ * The address is the hash of the script, the script is revealed by transactions spending from it.
+ */
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub enum Script {
+    /**
+     * The transaction is signed by the key
+     */
+    Signature(PublicKeyStr),
+    /**
+     * The transaction reveals a preimage of the hex encoded SHA-256 hash
+     */
+    HashPreimage(String),
+    /**
+     * The transaction is put into a block at or after the height or timestamp
+     */
+    After(TimeLock),
+    And(Vec<Script>),
+    Or(Vec<Script>),
+    /**
+     * At least `threshold` of the scripts are met
+     */
+    Threshold { threshold: u32, scripts: Vec<Script> },
+    /**
+     * The transaction is put into a block below the height or before the timestamp
+     */
+    Before(TimeLock),
+}
+
+/**
+ * What a script is evaluated against, signatures of the transaction must be verified already
+ */
+pub struct ScriptContext {
+    signers: HashSet<PublicKeyStr>,
+    preimage_hashes: HashSet<String>,
+    at: BlockTime,
+}
+
+impl Script {
+    /**
+     * Reads script from a JSON file
+     */
+    pub fn load(path: &Path) -> Result<Script> {
+        let json = fs::read_to_string(path).map_err(|err| anyhow!("Unable to read script file {}: {}", path.display(), err))?;
+        let script: Script = serde_json::from_str(&json).map_err(|err| anyhow!("Script file {} is malformed: {}", path.display(), err))?;
+        script.validate()?;
+        Ok(script)
+    }
+
+    /**
+     * Makes sure the script is well formed and within the depth and cost limits
+     */
+    pub fn validate(&self) -> Result<()> {
+        let cost = self.cost(1)?;
+        if cost > MAX_SCRIPT_COST {
+            return Err(anyhow!("Script cost {} exceeds the limit {}", cost, MAX_SCRIPT_COST));
+        }
+        Ok(())
+    }
+
+    fn cost(&self, depth: usize) -> Result<u64> {
+        if depth > MAX_SCRIPT_DEPTH {
+            return Err(anyhow!("Script is nested deeper than {}", MAX_SCRIPT_DEPTH));
+        }
+        match self {
+            Script::Signature(_) => Ok(SIGNATURE_COST),
+            Script::HashPreimage(hash) => match hex::decode(hash) {
+                Ok(bytes) if bytes.len() == 32 => Ok(HASH_PREIMAGE_COST),
+                _ => Err(anyhow!("Script hash {} is not a hex encoded SHA-256 hash", hash)),
+            },
+            Script::After(_) | Script::Before(_) => Ok(OPERATOR_COST),
+            Script::And(scripts) | Script::Or(scripts) => Self::total_cost(scripts, depth),
+            Script::Threshold { threshold, scripts } => {
+                if *threshold == 0 || *threshold as usize > scripts.len() {
+                    return Err(anyhow!("Script threshold must be between 1 and {}, {} given", scripts.len(), threshold));
+                }
+                Self::total_cost(scripts, depth)
+            },
+        }
+    }
+
+    fn total_cost(scripts: &[Script], depth: usize) -> Result<u64> {
+        if scripts.is_empty() {
+            return Err(anyhow!("Script operator must have at least one operand"));
+        }
+        // the limit is checked by the caller, saturation only keeps the sum from overflowing
+        scripts.iter().try_fold(OPERATOR_COST, |total, script| Ok(total.saturating_add(script.cost(depth + 1)?)))
+    }
+
+    /**
+     * Script must be valid
+     */
+    pub fn evaluate(&self, context: &ScriptContext) -> bool {
+        match self {
+            Script::Signature(key) => context.signers.contains(key),
+            Script::HashPreimage(hash) => context.preimage_hashes.contains(&hash.to_lowercase()),
+            Script::After(lock) => lock.is_unlocked_at(&context.at),
+            Script::Before(lock) => !lock.is_unlocked_at(&context.at),
+            Script::And(scripts) => scripts.iter().all(|script| script.evaluate(context)),
+            Script::Or(scripts) => scripts.iter().any(|script| script.evaluate(context)),
+            Script::Threshold { threshold, scripts } => scripts.iter().filter(|script| script.evaluate(context)).count() >= *threshold as usize,
+        }
+    }
+
+    /**
+     * Keys the script checks signatures of
+     */
+    pub fn keys(&self) -> Vec<&PublicKeyStr> {
+        match self {
+            Script::Signature(key) => vec![key],
+            Script::HashPreimage(_) | Script::After(_) | Script::Before(_) => Vec::new(),
+            Script::And(scripts) | Script::Or(scripts) | Script::Threshold { scripts, .. } => scripts.iter().flat_map(Script::keys).collect(),
+        }
+    }
+
+    pub fn address(&self) -> PublicKeyStr {
+        PublicKeyStr(HexString(format!("{}{}", SCRIPT_ADDRESS_PREFIX, hex::encode(self.canonical_hash()))))
+    }
+
+    pub fn is_script_address(address: &PublicKeyStr) -> bool {
+        address.0.0.starts_with(SCRIPT_ADDRESS_PREFIX)
+    }
+}
+
+impl ScriptContext {
+    /**
+     * `preimages` are hex encoded
+     */
+    pub fn new<'a>(signers: impl IntoIterator<Item = &'a PublicKeyStr>, preimages: &[String], at: &BlockTime) -> Result<ScriptContext> {
+        if preimages.len() > MAX_PREIMAGES {
+            return Err(anyhow!("Transaction reveals {} preimages, at most {} are allowed", preimages.len(), MAX_PREIMAGES));
+        }
+        let preimage_hashes = preimages.iter().map(|preimage| {
+            let bytes = hex::decode(preimage).map_err(|err| anyhow!("Preimage {} is not hex encoded: {}", preimage, err))?;
+            if bytes.len() > MAX_PREIMAGE_BYTES {
+                return Err(anyhow!("Preimage has {} bytes, at most {} are allowed", bytes.len(), MAX_PREIMAGE_BYTES));
+            }
+            Ok(hex::encode(Sha256::digest(bytes)))
+        }).collect::<Result<HashSet<_>>>()?;
+
+        Ok(ScriptContext { signers: signers.into_iter().cloned().collect(), preimage_hashes, at: *at })
+    }
+}
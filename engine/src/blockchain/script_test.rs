@@ -0,0 +1,105 @@
+#[cfg(test)]
+mod tests {
+    use sha1::Digest;
+    use sha2::Sha256;
+
+    use crate::{blockchain::{transaction::Transaction, coin_selection::CoinSelection, blockchain::BlockChain, genesis::{Genesis, GenesisAllocation}, script::{Script, ScriptContext}, signed_balanced_transaction::Witness, utxo::{TimeLock, BlockTime}}, encryption::generate_rsa_keypair_custom, model::PublicKeyStr};
+
+    const AT: BlockTime = BlockTime { height: 10, timestamp: 1000 };
+
+    fn context(signers: &[&str], preimages: &[&str]) -> ScriptContext {
+        let signers: Vec<PublicKeyStr> = signers.iter().map(|signer| PublicKeyStr::from_str(signer)).collect();
+        let preimages: Vec<String> = preimages.iter().map(hex::encode).collect();
+        ScriptContext::new(&signers, &preimages, &AT).unwrap()
+    }
+
+    fn signature(key: &str) -> Script {
+        Script::Signature(PublicKeyStr::from_str(key))
+    }
+
+    fn hash_of(preimage: &str) -> Script {
+        Script::HashPreimage(hex::encode(Sha256::digest(preimage)))
+    }
+
+    #[test]
+    fn opcodes_are_evaluated() {
+        assert!(signature("a").evaluate(&context(&["a"], &[])), "Signature of the key must be accepted");
+        assert!(!signature("a").evaluate(&context(&["b"], &[])), "Signature of another key must be rejected");
+
+        assert!(hash_of("secret").evaluate(&context(&[], &["secret"])), "Preimage must be accepted");
+        assert!(!hash_of("secret").evaluate(&context(&[], &["guess"])), "Wrong preimage must be rejected");
+
+        assert!(Script::After(TimeLock::Height(10)).evaluate(&context(&[], &[])), "Height lock must expire at its height");
+        assert!(!Script::After(TimeLock::Height(11)).evaluate(&context(&[], &[])), "Height lock must hold below its height");
+        assert!(Script::After(TimeLock::Timestamp(1000)).evaluate(&context(&[], &[])), "Timestamp lock must expire at its timestamp");
+        assert!(!Script::After(TimeLock::Timestamp(1001)).evaluate(&context(&[], &[])), "Timestamp lock must hold before its timestamp");
+
+        assert!(Script::Before(TimeLock::Height(11)).evaluate(&context(&[], &[])), "Deadline at a height must be met below it");
+        assert!(!Script::Before(TimeLock::Height(10)).evaluate(&context(&[], &[])), "Deadline at a height must be missed at it");
+        assert!(Script::Before(TimeLock::Timestamp(1001)).evaluate(&context(&[], &[])), "Deadline at a timestamp must be met before it");
+        assert!(!Script::Before(TimeLock::Timestamp(1000)).evaluate(&context(&[], &[])), "Deadline at a timestamp must be missed at it");
+
+        let and = Script::And(vec![signature("a"), hash_of("secret")]);
+        assert!(and.evaluate(&context(&["a"], &["secret"])), "All conditions are met");
+        assert!(!and.evaluate(&context(&["a"], &[])), "And needs all conditions");
+
+        let or = Script::Or(vec![signature("a"), signature("b")]);
+        assert!(or.evaluate(&context(&["b"], &[])), "One condition is met");
+        assert!(!or.evaluate(&context(&["c"], &[])), "Or needs at least one condition");
+
+        let threshold = Script::Threshold { threshold: 2, scripts: vec![signature("a"), signature("b"), hash_of("secret")] };
+        assert!(threshold.evaluate(&context(&["b"], &["secret"])), "Two of three conditions are met");
+        assert!(!threshold.evaluate(&context(&["a", "c"], &[])), "Threshold needs two conditions");
+    }
+
+    #[test]
+    fn scripts_exceeding_limits_are_rejected() {
+        let err = (0..8).fold(signature("a"), |script, _| Script::And(vec![script])).validate().unwrap_err();
+        assert_eq!(err.to_string(), "Script is nested deeper than 8");
+        (0..7).fold(signature("a"), |script, _| Script::And(vec![script])).validate().unwrap();
+
+        let err = Script::Or((0..20).map(|idx| signature(&idx.to_string())).collect()).validate().unwrap_err();
+        assert_eq!(err.to_string(), "Script cost 101 exceeds the limit 100");
+
+        assert!(Script::Threshold { threshold: 3, scripts: vec![signature("a"), signature("b")] }.validate().is_err(), "Threshold can't exceed number of scripts");
+        assert!(Script::Threshold { threshold: 0, scripts: vec![signature("a")] }.validate().is_err(), "Threshold must be positive");
+        assert!(Script::And(Vec::new()).validate().is_err(), "Operator needs operands");
+        assert!(Script::HashPreimage("abcd".to_owned()).validate().is_err(), "Hash must be a SHA-256 hash");
+
+        let preimages: Vec<String> = (0..17).map(|idx| hex::encode([idx])).collect();
+        assert!(ScriptContext::new(&[], &preimages, &AT).is_err(), "Too many preimages must be rejected");
+        assert!(ScriptContext::new(&[], &[hex::encode([0; 65])], &AT).is_err(), "Too long preimage must be rejected");
+    }
+
+    #[test]
+    fn script_outputs_are_spent_when_conditions_are_met() {
+        let (validator_private_key, validator_public_key) = &generate_rsa_keypair_custom().unwrap();
+        let (priv_1, pub_1) = &generate_rsa_keypair_custom().unwrap();
+        let (priv_2, pub_2) = &generate_rsa_keypair_custom().unwrap();
+
+        let script = Script::And(vec![Script::Signature(pub_2.clone()), hash_of("secret")]);
+        let address = &script.address();
+        assert!(Script::is_script_address(address), "Address is wrong: {}", address);
+
+        let mut blockchain = BlockChain::new(validator_public_key, Genesis::new("test", vec![GenesisAllocation::new(pub_1, 10)], Vec::new()));
+        Transaction::new(pub_1, address, 6)
+            .balance_transaction(&blockchain, 0, CoinSelection::default()).unwrap()
+            .sign(&priv_1.try_into().unwrap()).unwrap()
+            .commit(&mut blockchain, validator_private_key).unwrap();
+
+        let transaction = Transaction::new(address, pub_2, 6).balance_transaction(&blockchain, 0, CoinSelection::default()).unwrap();
+        let signed = transaction.sign(&priv_2.try_into().unwrap()).unwrap();
+
+        let err = signed.clone().commit(&mut blockchain, validator_private_key).unwrap_err();
+        assert!(err.to_string().contains("is not revealed"), "Error is wrong: {}", err);
+
+        let wrong_preimage = Witness { scripts: vec![script.clone()], preimages: vec![hex::encode("guess")], ..Witness::default() };
+        let err = signed.clone().with_witness(wrong_preimage).commit(&mut blockchain, validator_private_key).unwrap_err();
+        assert!(err.to_string().contains("are not met"), "Error is wrong: {}", err);
+
+        let witness = Witness { scripts: vec![script], preimages: vec![hex::encode("secret")], ..Witness::default() };
+        signed.with_witness(witness).commit(&mut blockchain, validator_private_key).unwrap();
+        assert_eq!(blockchain.balance(pub_2).unwrap().amount, 6, "Balance of the recipient is wrong");
+        assert_eq!(blockchain.audit(), Ok(()));
+    }
+}
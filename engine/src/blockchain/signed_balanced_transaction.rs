@@ -1,74 +1,298 @@
+use std::collections::{HashSet, BTreeSet};
+
 use crate::model::{Signature, PublicKeyStr, PrivateKeyStr};
 use anyhow::{Result, anyhow};
+use rsa::RsaPublicKey;
 use serde::{Serialize, Deserialize};
-use super::{utxo::UnspentOutput, blockchain::BlockChain, transaction_id::TransactionId, balanced_transaction::{BalancedTransaction}, cbor::Cbor, block::Block};
+use super::{asset::AssetId, utxo::{UnspentOutput, OutPoint, TransactionOutput, BlockTime}, blockchain::BlockChain, transaction_id::TransactionId, balanced_transaction::{BalancedTransaction}, cbor::Cbor, block::Block, encoding::CanonicalEncode, multisig::Multisig, script::{Script, ScriptContext}};
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct SignedBalancedTransaction {
     pub balanced_transaction: BalancedTransaction,
+    /**
+     * One signature per distinct address of the inputs, multisig addresses are signed by their keys instead
+     */
+    pub signatures: Vec<InputSignature>,
+    #[serde(default)]
+    pub witness: Witness,
+}
+
+/**
+ * Data revealed by the transaction to spend from multisig and script addresses.
+ * Like signatures, it's not part of the transaction id.
+ */
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Witness {
+    /**
+     * Policies of the multisig addresses spent by the transaction
+     */
+    #[serde(default)]
+    pub multisigs: Vec<Multisig>,
+    /**
+     * Scripts of the script addresses spent by the transaction
+     */
+    #[serde(default)]
+    pub scripts: Vec<Script>,
+    /**
+     * Hex encoded preimages of the hashes the scripts check
+     */
+    #[serde(default)]
+    pub preimages: Vec<String>,
+}
+
+/**
+ * Policy an address is the hash of, it's revealed by transactions spending from the address
+ */
+trait AddressPolicy {
+    const KIND: &'static str;
+
+    fn validate(&self) -> Result<()>;
+    fn address(&self) -> PublicKeyStr;
+    fn is_policy_address(address: &PublicKeyStr) -> bool;
+}
+
+impl AddressPolicy for Multisig {
+    const KIND: &'static str = "Multisig";
+
+    fn validate(&self) -> Result<()> {
+        Multisig::validate(self)
+    }
+
+    fn address(&self) -> PublicKeyStr {
+        Multisig::address(self)
+    }
+
+    fn is_policy_address(address: &PublicKeyStr) -> bool {
+        Multisig::is_multisig_address(address)
+    }
+}
+
+impl AddressPolicy for Script {
+    const KIND: &'static str = "Script";
+
+    fn validate(&self) -> Result<()> {
+        Script::validate(self)
+    }
+
+    fn address(&self) -> PublicKeyStr {
+        Script::address(self)
+    }
+
+    fn is_policy_address(address: &PublicKeyStr) -> bool {
+        Script::is_script_address(address)
+    }
+}
+
+/**
+ * Revealed policy of every address of its kind among the given ones, all revealed policies must be spent from
+ */
+fn revealed<'a, T: AddressPolicy>(policies: &'a [T], input_addresses: &[&PublicKeyStr]) -> Result<Vec<&'a T>> {
+    let mut revealed: Vec<(PublicKeyStr, &T)> = Vec::new();
+    for policy in policies {
+        policy.validate()?;
+        let address = policy.address();
+        if !input_addresses.contains(&&address) {
+            return Err(anyhow!("{} {} owns none of the inputs", T::KIND, address));
+        }
+        if revealed.iter().any(|(other, _)| *other == address) {
+            return Err(anyhow!("{} {} is revealed more than once", T::KIND, address));
+        }
+        revealed.push((address, policy));
+    }
+
+    if let Some(address) = input_addresses.iter().find(|address| T::is_policy_address(address) && !revealed.iter().any(|(other, _)| other == **address)) {
+        return Err(anyhow!("{} {} is not revealed", T::KIND, address));
+    }
+    Ok(revealed.into_iter().map(|(_, policy)| policy).collect())
+}
+
+/**
+ * Key addresses are public keys, other addresses are hashes of their policies
+ */
+fn is_key_address(address: &PublicKeyStr) -> bool {
+    !Multisig::is_multisig_address(address) && !Script::is_script_address(address)
+}
+
+/**
+ * Signature of the balanced transaction made by an owner of some of its inputs
+ */
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct InputSignature {
+    pub address: PublicKeyStr,
     pub signature: Signature,
 }
 
 impl SignedBalancedTransaction {
 
-    pub fn new(balanced_transaction: &BalancedTransaction, signature: &Signature) -> SignedBalancedTransaction {
+    pub fn new(balanced_transaction: &BalancedTransaction, signatures: &[InputSignature]) -> SignedBalancedTransaction {
         SignedBalancedTransaction {
             balanced_transaction: balanced_transaction.clone(),
-            signature: signature.clone(),
+            signatures: signatures.to_vec(),
+            witness: Witness::default(),
         }
     }
 
-    pub fn id(&self) -> &TransactionId {
-        &self.balanced_transaction.id
+    /**
+     * Reveals what's needed to spend from multisig and script addresses
+     */
+    pub fn with_witness(mut self, witness: Witness) -> SignedBalancedTransaction {
+        self.witness = witness;
+        self
     }
 
-    pub fn inputs(&self) -> &Vec<UnspentOutput> {
+    /**
+     * Id of the unsigned transaction, outputs are identified by the id and their index
+     */
+    pub fn id(&self) -> TransactionId {
+        self.balanced_transaction.id()
+    }
+
+    pub fn inputs(&self) -> &Vec<OutPoint> {
         &self.balanced_transaction.inputs
     }
 
-    pub fn outputs(&self) -> &Vec<UnspentOutput> {
+    pub fn outputs(&self) -> &Vec<TransactionOutput> {
         &self.balanced_transaction.outputs
     }
 
     /**
-     * Ensures that input and output balances match.
+     * Outpoints of the outputs, they can be spent once the transaction is committed
      */
-    pub fn check_balanced(&self) -> Result<()> {
-        let mut input_amt: u64 = 0;
-        let mut output_amt: u64 = 0;
+    pub fn unspent_outputs(&self) -> Vec<UnspentOutput> {
+        let txid = self.id();
+        self.outputs().iter().enumerate()
+            .map(|(index, output)| UnspentOutput::new(OutPoint::new(&txid, index as u32), output))
+            .collect()
+    }
 
-        for input in self.inputs() {
-            input_amt += input.amount;
-        }
-        for output in self.outputs() {
-            output_amt += output.amount;
+    /**
+     * Creates transaction paying the fees collected in a block to its proposer.
+     * It has no inputs and is always the first transaction of the block.
+     */
+    pub fn reward(chain_id: &str, height: u64, proposer: &PublicKeyStr, amount: u64) -> SignedBalancedTransaction {
+        SignedBalancedTransaction {
+            balanced_transaction: BalancedTransaction {
+                chain_id: chain_id.to_owned(),
+                inputs: Vec::new(),
+                outputs: vec![TransactionOutput::new(proposer, amount)],
+                reward_height: Some(height),
+                issuance: None,
+                mint: None,
+            },
+            signatures: Vec::new(),
+            witness: Witness::default(),
         }
-        if input_amt != output_amt {
-            Err(anyhow!("Transaction input and output amounts don't match"))
-        } else {
-            Ok(())
+    }
+
+    pub fn is_reward(&self) -> bool {
+        self.balanced_transaction.reward_height.is_some()
+    }
+
+    /**
+     * Fee paid to the validator, inputs minus outputs in the native coin.
+     * Every other asset must balance exactly, the amount issued or the token minted by the transaction counts as its input.
+     * `spent` are the outputs referenced by the inputs, in the same order.
+     */
+    pub fn fee(&self, spent: &[UnspentOutput]) -> Result<u64> {
+        let input_amt = |asset: Option<&AssetId>| total_amount(spent.iter().filter(|utxo| utxo.asset.as_ref() == asset).map(|utxo| utxo.amount));
+        let output_amt = |asset: Option<&AssetId>| total_amount(self.outputs().iter().filter(|output| output.asset.as_ref() == asset).map(|output| output.amount));
+
+        let issued: Vec<(AssetId, u64)> = self.balanced_transaction.issuance.iter().map(|issuance| (issuance.asset_id(), issuance.amount))
+            .chain(self.balanced_transaction.minted_token().map(|token| (token, 1)))
+            .collect();
+        let assets: BTreeSet<&AssetId> = spent.iter().filter_map(|utxo| utxo.asset.as_ref())
+            .chain(self.outputs().iter().filter_map(|output| output.asset.as_ref()))
+            .chain(issued.iter().map(|(asset, _)| asset))
+            .collect();
+        for asset in assets {
+            let issued_amt = issued.iter().filter(|(issued, _)| issued == asset).map(|(_, amount)| *amount);
+            let asset_input_amt = total_amount(issued_amt.chain([input_amt(Some(asset))?]))?;
+            let asset_output_amt = output_amt(Some(asset))?;
+            if asset_input_amt != asset_output_amt {
+                return Err(anyhow!("Transaction outputs ({}) of asset {} differ from its inputs ({})", asset_output_amt, asset, asset_input_amt));
+            }
         }
+
+        let input_amt = input_amt(None)?;
+        let output_amt = output_amt(None)?;
+        input_amt.checked_sub(output_amt)
+            .ok_or(anyhow!("Transaction outputs ({}) exceed its inputs ({})", output_amt, input_amt))
+    }
+
+    pub fn weight(&self) -> u64 {
+        self.balanced_transaction.weight()
     }
 
     /**
-     * Returns address from which funds will be sent.
+     * Makes sure every owner of the spent outputs, and the issuer of an asset, signed the transaction exactly once and nobody else did.
+     * Outputs of a multisig address need signatures of at least threshold of its keys, its policy must be revealed.
+     * Scripts of the spent script addresses must be revealed too, their keys may sign, see `verify_scripts`.
      */
-    pub fn get_from_address(&self) -> Result<&PublicKeyStr> {
-        if self.inputs().len() == 0 {
+    pub fn verify_signatures(&self, spent: &[UnspentOutput]) -> Result<()> {
+        if self.inputs().is_empty() {
             return Err(anyhow!("Transaction has no inputs"));
-        } else if self.inputs().len() == 0 {
-            Ok(&self.inputs()[0].address)
-        } else {
-            let mut address: &PublicKeyStr = &self.inputs()[0].address;
-            for input in &self.inputs()[1..] {
-                if *address != input.address {
-                    return Err(anyhow!("Transaction has multiple input addresses, this feature is not supported yet"));
-                }
-                
-                address = &input.address;
+        }
+
+        let mut seen = HashSet::new();
+        let input_addresses: Vec<&PublicKeyStr> = spent.iter().map(|utxo| &utxo.address).filter(|address| seen.insert(*address)).collect();
+        let multisigs = revealed(&self.witness.multisigs, &input_addresses)?;
+        let scripts = revealed(&self.witness.scripts, &input_addresses)?;
+
+        let issuer = self.balanced_transaction.issuance.as_ref().map(|issuance| &issuance.issuer);
+        let mut signers = HashSet::new();
+        for InputSignature { address, .. } in &self.signatures {
+            if !is_key_address(address) {
+                return Err(anyhow!("Address {} can't sign, keys of its policy sign instead", address));
             }
-            Ok(address)
+            let is_signer = input_addresses.contains(&address) || issuer == Some(address)
+                || multisigs.iter().any(|multisig| multisig.keys.contains(address))
+                || scripts.iter().any(|script| script.keys().contains(&address));
+            if !is_signer {
+                return Err(anyhow!("Transaction is signed by {} which owns none of its inputs", address));
+            }
+            if !signers.insert(address) {
+                return Err(anyhow!("Transaction is signed by {} more than once", address));
+            }
+        }
+
+        let bytes = self.balanced_transaction.canonical_bytes();
+        for InputSignature { address, signature } in &self.signatures {
+            let public_key = RsaPublicKey::try_from(address)?;
+            signature.verify(&public_key, &bytes)
+                .map_err(|_| anyhow!("Signature of input owner {} is invalid", address))?;
         }
+
+        for address in input_addresses.into_iter().filter(|address| is_key_address(address)) {
+            if !signers.contains(address) {
+                return Err(anyhow!("Transaction is not signed by input owner {}", address));
+            }
+        }
+        if let Some(issuer) = issuer.filter(|issuer| !signers.contains(issuer)) {
+            return Err(anyhow!("Transaction is not signed by issuer {}", issuer));
+        }
+        for multisig in multisigs {
+            let signed = multisig.keys.iter().filter(|key| signers.contains(key)).count();
+            if signed < multisig.threshold as usize {
+                return Err(anyhow!("Multisig {} needs {} signatures, {} given", multisig.address(), multisig.threshold, signed));
+            }
+        }
+
+        Ok(())
+    }
+
+    /**
+     * Makes sure conditions of the revealed scripts are met by the transaction put into a block at the given time.
+     * Signatures must be verified first, scripts only check who signed.
+     */
+    pub fn verify_scripts(&self, at: &BlockTime) -> Result<()> {
+        let signers = self.signatures.iter().map(|signature| &signature.address);
+        let context = ScriptContext::new(signers, &self.witness.preimages, at)?;
+        for script in &self.witness.scripts {
+            if !script.evaluate(&context) {
+                return Err(anyhow!("Conditions of script {} are not met", script.address()));
+            }
+        }
+        Ok(())
     }
 
     pub fn commit(&self, blockchain: &mut BlockChain, validator_private_key: &PrivateKeyStr) -> Result<Block> {
@@ -76,11 +300,20 @@ impl SignedBalancedTransaction {
         Ok(block)
     }
 
-    pub fn hash(&self) -> Result<Vec<u8>> {
-        Ok(Cbor::try_from(self)?.hash())
+    /**
+     * Hash of the canonical encoding of the transaction, signatures included.
+     * Blocks commit to witness hashes, so signatures of their transactions can't be changed either.
+     */
+    pub fn witness_hash(&self) -> Vec<u8> {
+        self.canonical_hash()
     }
 }
 
+pub fn total_amount(amounts: impl IntoIterator<Item = u64>) -> Result<u64> {
+    amounts.into_iter().try_fold(0_u64, |total, amount| total.checked_add(amount))
+        .ok_or(anyhow!("Total amount of utxos is too large"))
+}
+
 impl TryFrom<&Cbor> for SignedBalancedTransaction {
     type Error = anyhow::Error;
 
@@ -0,0 +1,43 @@
+use anyhow::{Result, anyhow};
+use serde::{Serialize, Deserialize};
+use sha1::Digest;
+use sha2::Sha256;
+
+use super::{asset::AssetId, encoding::token_id_bytes, utxo::OutPoint};
+
+const MAX_METADATA_BYTES: usize = 256;
+
+/**
+ * Mints a unique token, an asset of which exactly one unit exists and is always held by a single output.
+ * The token id is derived from the first input of the minting transaction, an output can only be spent once, so ids never repeat.
+ */
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct TokenMint {
+    /**
+     * Hex encoded bytes describing the token, they are never interpreted
+     */
+    pub metadata: String,
+}
+
+impl TokenMint {
+    pub fn new(metadata: &str) -> Result<TokenMint> {
+        let mint = TokenMint { metadata: metadata.to_lowercase() };
+        mint.validate()?;
+        Ok(mint)
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        let bytes = hex::decode(&self.metadata).map_err(|err| anyhow!("Token metadata {} is not hex encoded: {}", self.metadata, err))?;
+        if bytes.len() > MAX_METADATA_BYTES {
+            return Err(anyhow!("Token metadata has {} bytes, at most {} are allowed", bytes.len(), MAX_METADATA_BYTES));
+        }
+        Ok(())
+    }
+
+    /**
+     * Id of the token minted by a transaction with the given first input
+     */
+    pub fn token_id(first_input: &OutPoint) -> AssetId {
+        AssetId(hex::encode(Sha256::digest(token_id_bytes(first_input))))
+    }
+}
@@ -0,0 +1,55 @@
+#[cfg(test)]
+mod tests {
+    use crate::{blockchain::{transaction::Transaction, coin_selection::CoinSelection, blockchain::BlockChain, genesis::{Genesis, GenesisAllocation}, token::TokenMint, utxo::TransactionOutput}, encryption::generate_rsa_keypair_custom};
+
+    #[test]
+    fn tokens_are_minted_and_transferred_without_duplication() {
+        let (validator_private_key, validator_public_key) = &generate_rsa_keypair_custom().unwrap();
+        let (priv_1, pub_1) = &generate_rsa_keypair_custom().unwrap();
+        let (priv_2, pub_2) = &generate_rsa_keypair_custom().unwrap();
+        let mut blockchain = BlockChain::new(validator_public_key, Genesis::new("test", vec![GenesisAllocation::new(pub_1, 10)], Vec::new()));
+
+        let mint = Transaction::new(pub_1, pub_2, 1).with_mint(Some(TokenMint::new("cafe").unwrap()));
+        let minting = mint.balance_transaction(&blockchain, 1, CoinSelection::default()).unwrap();
+        let token = minting.minted_token().unwrap();
+        minting.sign(&priv_1.try_into().unwrap()).unwrap().commit(&mut blockchain, validator_private_key).unwrap();
+        assert_eq!(blockchain.utxos().token_output(&token).unwrap().address, *pub_2, "Recipient must own the token");
+        assert_eq!(blockchain.utxos().token(&token).unwrap().metadata, "cafe", "Metadata is wrong");
+        assert_eq!(blockchain.balance(pub_1).unwrap().amount, 7, "Fee must be paid in the native coin");
+
+        let other = mint.balance_transaction(&blockchain, 0, CoinSelection::default()).unwrap().minted_token().unwrap();
+        assert_ne!(token, other, "Every mint must get a new token id");
+        assert!(Transaction::new(pub_1, pub_2, 2).with_mint(Some(TokenMint::new("").unwrap())).balance_transaction(&blockchain, 0, CoinSelection::default()).is_err(), "Token quantity must be 1");
+
+        let transfer = Transaction::new(pub_2, pub_1, 1).with_asset(Some(token.clone()));
+        let mut duplicating = transfer.balance_transaction(&blockchain, 0, CoinSelection::default()).unwrap();
+        duplicating.outputs.push(TransactionOutput::new(pub_2, 0).with_asset(Some(token.clone())));
+        let err = duplicating.sign(&priv_2.try_into().unwrap()).unwrap().commit(&mut blockchain, validator_private_key).unwrap_err();
+        assert!(err.to_string().contains("must be held by an output of amount 1"), "Error is wrong: {}", err);
+
+        transfer.balance_transaction(&blockchain, 0, CoinSelection::default()).unwrap()
+            .sign(&priv_2.try_into().unwrap()).unwrap()
+            .commit(&mut blockchain, validator_private_key).unwrap();
+        assert_eq!(blockchain.utxos().token_output(&token).unwrap().address, *pub_1, "Token must move to the recipient");
+        assert!(blockchain.balance(pub_2).unwrap().assets.is_empty(), "Sender must not keep the token");
+        assert_eq!(blockchain.audit(), Ok(()));
+    }
+
+    #[test]
+    fn outputs_of_zero_amount_are_rejected() {
+        let (validator_private_key, validator_public_key) = &generate_rsa_keypair_custom().unwrap();
+        let (priv_1, pub_1) = &generate_rsa_keypair_custom().unwrap();
+        let (_, pub_2) = &generate_rsa_keypair_custom().unwrap();
+        let mut blockchain = BlockChain::new(validator_public_key, Genesis::new("test", vec![GenesisAllocation::new(pub_1, 10), GenesisAllocation::new(pub_2, 10)], Vec::new()));
+        // id of a token pub_2 could mint later by spending its genesis output
+        let future_token = TokenMint::token_id(&blockchain.utxos().unspent_for(pub_2).next().unwrap().outpoint);
+
+        for asset in [None, Some(future_token)] {
+            let mut balanced = Transaction::new(pub_1, pub_2, 3).balance_transaction(&blockchain, 0, CoinSelection::default()).unwrap();
+            balanced.outputs.push(TransactionOutput::new(pub_1, 0).with_asset(asset.clone()));
+            let err = balanced.sign(&priv_1.try_into().unwrap()).unwrap().commit(&mut blockchain, validator_private_key).unwrap_err();
+            assert!(err.to_string().contains("zero amount"), "Error is wrong for asset {:?}: {}", asset, err);
+        }
+        assert_eq!(blockchain.balance(pub_1).unwrap().amount, 10, "Nothing must be committed");
+    }
+}
@@ -1,70 +1,167 @@
-use std::collections::HashSet;
-
 use crate::model::PublicKeyStr;
 use anyhow::{Result, anyhow};
 
-use super::{blockchain::BlockChain, utxo::UnspentOutput, balanced_transaction::BalancedTransaction, transaction_id::TransactionId};
+use super::{asset::{AssetId, Issuance}, token::TokenMint, blockchain::BlockChain, utxo::{TransactionOutput, TimeLock, UnspentOutput}, balanced_transaction::BalancedTransaction, coin_selection::CoinSelection};
 
 pub struct Transaction {
-    pub from: PublicKeyStr,
-    pub to: PublicKeyStr,
-    pub amount: u64,
+    /**
+     * Funds are taken from any of the addresses, the change goes back to the first one
+     */
+    pub from: Vec<PublicKeyStr>,
+    /**
+     * Every recipient gets its own output, in the given order
+     */
+    pub to: Vec<(PublicKeyStr, u64)>,
+    /**
+     * Outputs paid to the recipients can't be spent until the lock expires, the change is never locked
+     */
+    pub lock: Option<TimeLock>,
+    /**
+     * Asset paid to the recipients, the native coin if None
+     */
+    pub asset: Option<AssetId>,
+    /**
+     * Name of a new asset issued by the first sender, the recipients get the whole issued amount
+     */
+    pub issue: Option<String>,
+    /**
+     * Unique token minted to the single recipient, who must be paid 1
+     */
+    pub mint: Option<TokenMint>,
 }
 
 impl Transaction {
     pub fn new(from: &PublicKeyStr, to: &PublicKeyStr, amount: u64) -> Transaction {
-        Transaction { from: from.clone(), to: to.clone(), amount }
+        Transaction { from: vec![from.clone()], to: vec![(to.clone(), amount)], lock: None, asset: None, issue: None, mint: None }
+    }
+
+    pub fn with_recipients(from: &[PublicKeyStr], to: &[(PublicKeyStr, u64)]) -> Transaction {
+        Transaction { from: from.to_vec(), to: to.to_vec(), lock: None, asset: None, issue: None, mint: None }
+    }
+
+    pub fn with_lock(mut self, lock: Option<TimeLock>) -> Transaction {
+        self.lock = lock;
+        self
     }
 
-    pub fn balance_transaction(&self, blockchain: &BlockChain) -> Result<BalancedTransaction> {
-        let mut unspent_utxos = HashSet::new();
-        if self.from == blockchain.initial_utxo.address {
-            unspent_utxos.insert(blockchain.initial_utxo.clone());
+    pub fn with_asset(mut self, asset: Option<AssetId>) -> Transaction {
+        self.asset = asset;
+        self
+    }
+
+    pub fn with_issuance(mut self, name: Option<String>) -> Transaction {
+        self.issue = name;
+        self
+    }
+
+    pub fn with_mint(mut self, mint: Option<TokenMint>) -> Transaction {
+        self.mint = mint;
+        self
+    }
+
+    /**
+     * Total amount paid to all recipients
+     */
+    pub fn amount(&self) -> Result<u64> {
+        if self.to.is_empty() {
+            return Err(anyhow!("Transaction has no recipients"));
         }
+        self.to.iter().try_fold(0_u64, |total, (address, amount)| {
+            if *amount == 0 {
+                return Err(anyhow!("Amount paid to {} must be positive", address));
+            }
+            total.checked_add(*amount).ok_or(anyhow!("Total amount of the transaction is too large"))
+        })
+    }
 
-        for block in &blockchain.blocks {
-            for utxo in block.transaction.outputs() {
-                if utxo.address == self.from {
-                    unspent_utxos.insert(utxo.clone());
-                }
+    /**
+     * Selects inputs with the given strategy to pay all recipients and the fee, the fee is `fee_rate` per input and output.
+     * Outputs of a transferred asset are selected separately and its change always goes back, the fee is paid in the native coin.
+     * If the native change is not worth the fee of an extra output, it's left to the validator.
+     * A minted token gets its id from the first input, so minting always spends at least one native output.
+     * Outputs which are still locked in the next block are never selected.
+     */
+    pub fn balance_transaction(&self, blockchain: &BlockChain, fee_rate: u64, coin_selection: CoinSelection) -> Result<BalancedTransaction> {
+        let change_address = self.from.first().ok_or(anyhow!("Transaction has no sender"))?;
+        let total_amount = self.amount()?;
+        let not_enough = |asset: Option<&AssetId>| {
+            let senders: Vec<String> = self.from.iter().map(|address| address.to_string()).collect();
+            match asset {
+                Some(asset) => anyhow!("Not enough of asset {} for {}", asset, senders.join(", ")),
+                None => anyhow!("Not enough funds for {}", senders.join(", ")),
             }
-        }
+        };
 
-        for block in &blockchain.blocks {
-            for utxo in block.transaction.inputs() {
-                if utxo.address == self.from {
-                    unspent_utxos.remove(utxo);
-                }
+        let (issuance, asset) = match (&self.issue, &self.asset) {
+            (Some(_), Some(_)) => return Err(anyhow!("Transaction can't both issue and transfer an asset")),
+            (Some(name), None) => {
+                let issuance = Issuance::new(change_address, name, total_amount);
+                let asset = issuance.asset_id();
+                (Some(issuance), Some(asset))
+            },
+            (None, asset) => (None, asset.clone()),
+        };
+        if self.mint.is_some() {
+            if issuance.is_some() || asset.is_some() {
+                return Err(anyhow!("Transaction can't both mint a token and issue or transfer an asset"));
+            }
+            if self.to.len() != 1 || total_amount != 1 {
+                return Err(anyhow!("Minted token must be paid to a single recipient with amount 1"));
             }
         }
 
-        let mut amt = 0 as u64;
-        let mut selected_utxos: Vec<UnspentOutput> = Vec::new();
-        for utxo in unspent_utxos.iter() {
-            if amt >= self.amount {
-                break;
-            } else {
-                amt += utxo.amount;
-                selected_utxos.push(utxo.clone());
+        let next_block_time = blockchain.next_block_time();
+        let candidates: Vec<&UnspentOutput> = blockchain.utxos().unspent_for_all(&self.from).into_iter()
+            .filter(|utxo| utxo.is_spendable_at(&next_block_time))
+            .collect();
+        let candidates_of = |asset: Option<&AssetId>| -> Vec<&UnspentOutput> {
+            candidates.iter().filter(|utxo| utxo.asset.as_ref() == asset).cloned().collect()
+        };
+
+        // a transferred asset pays no fee, so its outputs are selected for the exact amount
+        let mut asset_inputs = Vec::new();
+        let mut asset_change = None;
+        if let (None, Some(asset)) = (&issuance, &asset) {
+            asset_inputs = coin_selection.select(&candidates_of(Some(asset)), total_amount, 0, 0).ok_or_else(|| not_enough(Some(asset)))?;
+            let amt: u64 = asset_inputs.iter().map(|utxo| utxo.amount).sum();
+            if amt > total_amount {
+                asset_change = Some(TransactionOutput::new(change_address, amt - total_amount).with_asset(Some(asset.clone())));
             }
         }
 
-        if amt < self.amount {
-            return Err(anyhow!("Not enough funds for {}", self.from));
-        }
+        let native_amount = if asset.is_some() || self.mint.is_some() { 0 } else { total_amount };
+        let asset_weight = asset_inputs.len() + usize::from(asset_change.is_some()) + self.to.len();
+        let required = |inputs: usize, outputs: usize| {
+            fee_rate.checked_mul((asset_weight + inputs + outputs) as u64)
+                .and_then(|fee| fee.checked_add(native_amount))
+                .ok_or(anyhow!("Fee of the transaction is too large"))
+        };
+        let target = required(0, 0)?.max(u64::from(self.mint.is_some()));
+        let selected_utxos = coin_selection.select(&candidates_of(None), target, fee_rate, fee_rate)
+            .ok_or_else(|| not_enough(None))?;
+        let asset = match (&self.mint, selected_utxos.first()) {
+            (Some(_), Some(first_input)) => Some(TokenMint::token_id(&first_input.outpoint)),
+            _ => asset,
+        };
+        let amt: u64 = selected_utxos.iter().map(|utxo| utxo.amount).sum();
 
-        let mut output_utxos: Vec<UnspentOutput> = Vec::new();
-        if amt > self.amount {
-            let change_utxo = UnspentOutput::new(&self.from.clone(), amt - self.amount);
-            output_utxos.push(change_utxo);
+        let mut outputs: Vec<TransactionOutput> = Vec::new();
+        let required_with_change = required(selected_utxos.len(), 1)?;
+        if amt > required_with_change {
+            outputs.push(TransactionOutput::new(change_address, amt - required_with_change));
+        }
+        outputs.extend(asset_change);
+        for (address, amount) in &self.to {
+            outputs.push(TransactionOutput::locked(address, *amount, self.lock).with_asset(asset.clone()));
         }
-        let transfer_utxo = UnspentOutput::new(&self.to.clone(), self.amount);
-        output_utxos.push(transfer_utxo);
 
         Ok(BalancedTransaction {
-            id: TransactionId::generate(),
-            inputs: selected_utxos,
-            outputs: output_utxos,
+            chain_id: blockchain.genesis.chain_id.to_owned(),
+            inputs: asset_inputs.into_iter().chain(selected_utxos).map(|utxo| utxo.outpoint).collect(),
+            outputs,
+            reward_height: None,
+            issuance,
+            mint: self.mint.clone(),
         })
     }
 
@@ -1,17 +1,21 @@
-use serde::{Serialize, Deserialize};
+use std::fmt::{Display, Formatter};
 
-use super::uuid::Uuid;
+use serde::{Serialize, Deserialize};
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
-pub struct TransactionId(pub Uuid);
+/**
+ * Hex encoded hash of the transaction
+ */
+#[derive(Clone, Eq, Hash, PartialEq, PartialOrd, Ord, Debug, Serialize, Deserialize)]
+pub struct TransactionId(pub String);
 
 impl TransactionId {
     pub fn new(id: &str) -> TransactionId {
-        TransactionId(Uuid::new(id))
+        TransactionId(id.to_owned())
     }
+}
 
-    pub fn generate() -> TransactionId {
-        TransactionId(Uuid::generate())
+impl Display for TransactionId {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
     }
-
-}
\ No newline at end of file
+}
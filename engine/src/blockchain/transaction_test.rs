@@ -1,6 +1,6 @@
 #[cfg(test)]
 mod tests {
-    use crate::{blockchain::{transaction::Transaction, blockchain::BlockChain, utxo::UnspentOutput}, encryption::{generate_rsa_keypair_custom}};
+    use crate::{blockchain::{transaction::Transaction, coin_selection::CoinSelection, blockchain::BlockChain, genesis::{Genesis, GenesisAllocation}, utxo::OutPoint}, encryption::{generate_rsa_keypair_custom}, model::PublicKeyStr};
 
     #[test]
     fn balance_transaction_happy_path() {
@@ -8,16 +8,15 @@ mod tests {
         let (priv_1, pub_1) = &generate_rsa_keypair_custom().unwrap();
         let (priv_2, pub_2) = &generate_rsa_keypair_custom().unwrap();
         
-        let mut blockchain = BlockChain::new(validator_public_key, UnspentOutput::new(&pub_1, 10));
-        assert_eq!(blockchain.blocks.len(), 0, "Number of transactions is wrong");
+        let mut blockchain = BlockChain::new(validator_public_key, Genesis::new("test", vec![GenesisAllocation::new(pub_1, 10)], Vec::new()));
+        assert_eq!(blockchain.len(), 0, "Number of transactions is wrong");
         
         // first transaction
-        let transaction = Transaction::new(&pub_1, &pub_2, 10)
-            .balance_transaction(&blockchain).unwrap();
+        let transaction = Transaction::new(pub_1, pub_2, 10)
+            .balance_transaction(&blockchain, 0, CoinSelection::default()).unwrap();
 
         assert_eq!(transaction.inputs.len(), 1, "Number of inputs is wrong");
-        assert_eq!(transaction.inputs[0].address, *pub_1, "From address is wrong");
-        assert_eq!(transaction.inputs[0].amount, 10, "Input amount is wrong");
+        assert_eq!(transaction.inputs[0], blockchain.genesis.initial_utxos()[0].outpoint, "Input must spend the genesis allocation");
         assert_eq!(transaction.outputs.len(), 1, "Number of outputs is wrong");
         assert_eq!(transaction.outputs[0].address, *pub_2, "To address is wrong");
         assert_eq!(transaction.outputs[0].amount, 10, "Output amount is wrong");
@@ -25,25 +24,26 @@ mod tests {
         let signed_transaction = transaction.sign(&priv_1.try_into().unwrap()).unwrap();
 
         let block = blockchain.commit_transaction(&signed_transaction, validator_private_key).unwrap();
-        assert_eq!(blockchain.blocks.len(), 1, "Number of transactions is wrong");
+        assert_eq!(blockchain.len(), 1, "Number of transactions is wrong");
         assert_eq!(block.validator_signatures().len(), 1, "Number of signatures is wrong");
         assert_eq!(block.validator_signatures()[0].validator_public_key, *validator_public_key, "Number of signatures is wrong");
 
         // second transaction, with change
-        let transaction = Transaction::new(&pub_2, &pub_1, 5)
-            .balance_transaction(&blockchain)
+        let second_transaction = Transaction::new(pub_2, pub_1, 5)
+            .balance_transaction(&blockchain, 0, CoinSelection::default())
             .unwrap()
             .sign(&priv_2.try_into().unwrap())
             .unwrap()
             .commit(&mut blockchain, validator_private_key)
             .unwrap()
-            .transaction;
+            .transactions
+            .remove(0);
+        let transaction = &second_transaction;
 
-        assert_eq!(blockchain.blocks.len(), 2, "Number of transactions is wrong");
+        assert_eq!(blockchain.len(), 2, "Number of transactions is wrong");
         
         assert_eq!(transaction.inputs().len(), 1, "Number of inputs is wrong");
-        assert_eq!(transaction.inputs()[0].address, *pub_2, "From address is wrong");
-        assert_eq!(transaction.inputs()[0].amount, 10, "Input amount is wrong");
+        assert_eq!(transaction.inputs()[0], OutPoint::new(&signed_transaction.id(), 0), "Input must spend the output of the first transaction");
         assert_eq!(transaction.outputs().len(), 2, "Number of outputs is wrong");
         assert_eq!(transaction.outputs()[0].address, *pub_2, "Receiver address is wrong");
         assert_eq!(transaction.outputs()[0].amount, 5, "Output amount is wrong");
@@ -51,32 +51,33 @@ mod tests {
         assert_eq!(transaction.outputs()[1].amount, 5, "Output amount is wrong");
 
         // third transaction
-        let transaction = Transaction::new(&pub_2, &pub_1, 5)
-            .balance_transaction(&blockchain)
+        let third_transaction = Transaction::new(pub_2, pub_1, 5)
+            .balance_transaction(&blockchain, 0, CoinSelection::default())
             .unwrap()
             .sign(&priv_2.try_into().unwrap())
             .unwrap()
             .commit(&mut blockchain, validator_private_key)
-            .unwrap();
-        assert_eq!(blockchain.blocks.len(), 3, "Number of transactions is wrong");
+            .unwrap()
+            .transactions
+            .remove(0);
+        assert_eq!(blockchain.len(), 3, "Number of transactions is wrong");
 
         // fourth transaction
-        let transaction = Transaction::new(&pub_1, &pub_2, 8)
-            .balance_transaction(&blockchain)
+        let transaction = Transaction::new(pub_1, pub_2, 8)
+            .balance_transaction(&blockchain, 0, CoinSelection::default())
             .unwrap()
             .sign(&priv_1.try_into().unwrap())
             .unwrap()
             .commit(&mut blockchain, validator_private_key)
             .unwrap()
-            .transaction;
+            .transactions
+            .remove(0);
 
-        assert_eq!(blockchain.blocks.len(), 4, "Number of transactions is wrong");
+        assert_eq!(blockchain.len(), 4, "Number of transactions is wrong");
 
         assert_eq!(transaction.inputs().len(), 2, "Number of inputs is wrong");
-        assert_eq!(transaction.inputs()[0].address, *pub_1, "From address is wrong");
-        assert_eq!(transaction.inputs()[0].amount, 5, "First input amount is wrong");
-        assert_eq!(transaction.inputs()[1].address, *pub_1, "From address is wrong");
-        assert_eq!(transaction.inputs()[1].amount, 5, "Second input amount is wrong");
+        assert_eq!(transaction.inputs()[0], OutPoint::new(&second_transaction.id(), 1), "Older input must be spent first");
+        assert_eq!(transaction.inputs()[1], OutPoint::new(&third_transaction.id(), 0), "Second input is wrong");
         assert_eq!(transaction.outputs().len(), 2, "Number of outputs is wrong");
         assert_eq!(transaction.outputs()[0].address, *pub_1, "Receiver address is wrong");
         assert_eq!(transaction.outputs()[0].amount, 2, "Output amount is wrong");
@@ -88,4 +89,72 @@ mod tests {
     fn balance_transaction_not_enough_funds() {
     }
 
-}
\ No newline at end of file
+    #[test]
+    fn inputs_of_several_owners_are_signed_by_each_of_them() {
+        let (validator_private_key, validator_public_key) = &generate_rsa_keypair_custom().unwrap();
+        let (priv_1, pub_1) = &generate_rsa_keypair_custom().unwrap();
+        let (priv_2, pub_2) = &generate_rsa_keypair_custom().unwrap();
+        let (_, pub_3) = &generate_rsa_keypair_custom().unwrap();
+
+        let genesis = Genesis::new("test", vec![GenesisAllocation::new(pub_1, 6), GenesisAllocation::new(pub_2, 6)], Vec::new());
+        let mut blockchain = BlockChain::new(validator_public_key, genesis);
+
+        let transaction = Transaction::with_recipients(&[pub_1.clone(), pub_2.clone()], &[(pub_3.clone(), 10)]).balance_transaction(&blockchain, 0, CoinSelection::default()).unwrap();
+        assert_eq!(transaction.inputs.len(), 2, "Number of inputs is wrong");
+        assert_eq!(transaction.outputs[0].address, *pub_1, "Change must go to the first sender");
+        assert_eq!(transaction.outputs[0].amount, 2, "Change amount is wrong");
+
+        // every owner has to sign
+        let err = blockchain.verify_transaction(&transaction.sign(&priv_1.try_into().unwrap()).unwrap()).unwrap_err();
+        assert!(err.to_string().contains("is not signed by input owner"), "Error is wrong: {}", err);
+
+        let signed_transaction = transaction.sign_with(&[priv_2.try_into().unwrap(), priv_1.try_into().unwrap()]).unwrap();
+        assert_eq!(signed_transaction.signatures.len(), 2, "Number of signatures is wrong");
+
+        let mut missing_signature = signed_transaction.clone();
+        missing_signature.signatures.remove(1);
+        let err = blockchain.verify_transaction(&missing_signature).unwrap_err();
+        assert!(err.to_string().contains("is not signed by input owner"), "Error is wrong: {}", err);
+
+        let mut duplicated_signature = signed_transaction.clone();
+        duplicated_signature.signatures.push(duplicated_signature.signatures[0].clone());
+        let err = blockchain.verify_transaction(&duplicated_signature).unwrap_err();
+        assert!(err.to_string().contains("more than once"), "Error is wrong: {}", err);
+
+        // one owner signs on behalf of another
+        let mut forged_signature = signed_transaction.clone();
+        forged_signature.signatures[1].signature = forged_signature.signatures[0].signature.clone();
+        let err = blockchain.verify_transaction(&forged_signature).unwrap_err();
+        assert!(err.to_string().contains("is invalid"), "Error is wrong: {}", err);
+
+        signed_transaction.commit(&mut blockchain, validator_private_key).unwrap();
+        assert_eq!(blockchain.balance(pub_3).unwrap().amount, 10, "Balance of the receiver is wrong");
+        assert_eq!(blockchain.balance(pub_1).unwrap().amount, 2, "Balance of the first sender is wrong");
+        assert_eq!(blockchain.balance(pub_2).unwrap().amount, 0, "Balance of the second sender is wrong");
+    }
+
+    #[test]
+    fn one_transaction_pays_many_recipients() {
+        let (validator_private_key, validator_public_key) = &generate_rsa_keypair_custom().unwrap();
+        let (priv_1, pub_1) = &generate_rsa_keypair_custom().unwrap();
+        let (_, pub_2) = &generate_rsa_keypair_custom().unwrap();
+        let (_, pub_3) = &generate_rsa_keypair_custom().unwrap();
+
+        let mut blockchain = BlockChain::new(validator_public_key, Genesis::new("test", vec![GenesisAllocation::new(pub_1, 10)], Vec::new()));
+
+        let transaction = Transaction::with_recipients(std::slice::from_ref(pub_1), &[(pub_2.clone(), 3), (pub_3.clone(), 4), (pub_2.clone(), 1)])
+            .balance_transaction(&blockchain, 0, CoinSelection::default())
+            .unwrap();
+        let outputs: Vec<(PublicKeyStr, u64)> = transaction.outputs.iter().map(|output| (output.address.clone(), output.amount)).collect();
+        assert_eq!(outputs, vec![(pub_1.clone(), 2), (pub_2.clone(), 3), (pub_3.clone(), 4), (pub_2.clone(), 1)], "Outputs are wrong");
+
+        transaction.sign(&priv_1.try_into().unwrap()).unwrap().commit(&mut blockchain, validator_private_key).unwrap();
+        assert_eq!(blockchain.balance(pub_2).unwrap().amount, 4, "Balance of the first recipient is wrong");
+        assert_eq!(blockchain.balance(pub_3).unwrap().amount, 4, "Balance of the second recipient is wrong");
+
+        let err = Transaction::with_recipients(std::slice::from_ref(pub_1), &[(pub_2.clone(), 1), (pub_3.clone(), 0)]).balance_transaction(&blockchain, 0, CoinSelection::default()).unwrap_err();
+        assert!(err.to_string().contains("must be positive"), "Error is wrong: {}", err);
+        let err = Transaction::with_recipients(std::slice::from_ref(pub_1), &[(pub_2.clone(), 2), (pub_3.clone(), 1)]).balance_transaction(&blockchain, 0, CoinSelection::default()).unwrap_err();
+        assert!(err.to_string().contains("Not enough funds"), "Error is wrong: {}", err);
+    }
+}
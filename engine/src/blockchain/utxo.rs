@@ -1,47 +1,151 @@
+use std::fmt::{Display, Formatter};
+
+use anyhow::{Result, anyhow};
 use serde::{Serialize, Deserialize};
-use sha1::Digest;
-use sha2::Sha256;
 use crate::model::PublicKeyStr;
 
-use super::{uuid::Uuid, cbor::Cbor};
+use super::{cbor::Cbor, transaction_id::TransactionId, asset::AssetId};
+
+/**
+ * Reference to an output: id of the transaction which created it and index of the output in that transaction
+ */
+#[derive(Clone, Eq, Hash, PartialEq, PartialOrd, Ord, Debug, Serialize, Deserialize)]
+pub struct OutPoint {
+    pub txid: TransactionId,
+    pub index: u32,
+}
 
+/**
+ * Output of a transaction, it's identified by an outpoint once the transaction is committed
+ */
 #[derive(Clone, Eq, Hash, PartialEq, Debug, Serialize, Deserialize)]
-pub struct UnspentOutput {
-    pub id: UnspentOutputId,
+pub struct TransactionOutput {
     pub address: PublicKeyStr,
     pub amount: u64,
+    #[serde(default)]
+    pub lock: Option<TimeLock>,
+    /**
+     * Asset the amount is in, the native coin if not set
+     */
+    #[serde(default)]
+    pub asset: Option<AssetId>,
 }
 
+/**
+ * Output of a committed transaction which has not been spent yet
+ */
 #[derive(Clone, Eq, Hash, PartialEq, Debug, Serialize, Deserialize)]
-pub struct UnspentOutputId(pub String);
+pub struct UnspentOutput {
+    pub outpoint: OutPoint,
+    pub address: PublicKeyStr,
+    pub amount: u64,
+    #[serde(default)]
+    pub lock: Option<TimeLock>,
+    #[serde(default)]
+    pub asset: Option<AssetId>,
+}
 
-impl UnspentOutput {
-    pub fn initial_utxo(address: &PublicKeyStr, amount: u64) -> Self {
-        UnspentOutput {
-            id: UnspentOutputId("0".to_owned()),
+/**
+ * Output can't be spent by a block below the given height, or by a block with an earlier timestamp
+ */
+#[derive(Clone, Copy, Eq, Hash, PartialEq, Debug, Serialize, Deserialize)]
+pub enum TimeLock {
+    Height(u64),
+    /**
+     * Milliseconds since unix epoch
+     */
+    Timestamp(u64),
+}
+
+/**
+ * Height and timestamp of the block spending outputs, locks are checked against them
+ */
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub struct BlockTime {
+    pub height: u64,
+    pub timestamp: u64,
+}
+
+impl OutPoint {
+    pub fn new(txid: &TransactionId, index: u32) -> OutPoint {
+        OutPoint { txid: txid.clone(), index }
+    }
+}
+
+impl Display for OutPoint {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.txid, self.index)
+    }
+}
+
+impl TransactionOutput {
+    pub fn new(address: &PublicKeyStr, amount: u64) -> TransactionOutput {
+        TransactionOutput {
             address: address.clone(),
             amount,
+            lock: None,
+            asset: None,
         }
     }
 
-    pub fn new(address: &PublicKeyStr, amount: u64) -> UnspentOutput {
-        UnspentOutput {
-            id: UnspentOutputId(Uuid::generate().0.to_owned()),
+    pub fn locked(address: &PublicKeyStr, amount: u64, lock: Option<TimeLock>) -> TransactionOutput {
+        TransactionOutput {
             address: address.clone(),
             amount,
+            lock,
+            asset: None,
         }
     }
 
-    pub fn hash(&self) -> Vec<u8> {
-        let mut hasher = Sha256::new();
-        hasher.update(&self.id.0.as_bytes());
-        hasher.update(&self.address.0.0.as_bytes());
-        hasher.update(&self.amount.to_le_bytes());
-        hasher.finalize().to_vec()
+    pub fn with_asset(mut self, asset: Option<AssetId>) -> TransactionOutput {
+        self.asset = asset;
+        self
+    }
+}
+
+impl UnspentOutput {
+    pub fn new(outpoint: OutPoint, output: &TransactionOutput) -> UnspentOutput {
+        UnspentOutput {
+            outpoint,
+            address: output.address.clone(),
+            amount: output.amount,
+            lock: output.lock,
+            asset: output.asset.clone(),
+        }
     }
 
-    pub fn hash_str(&self) -> String {
-        hex::encode(self.hash())
+    pub fn is_spendable_at(&self, at: &BlockTime) -> bool {
+        self.lock.map(|lock| lock.is_unlocked_at(at)).unwrap_or(true)
+    }
+}
+
+impl TimeLock {
+    /**
+     * At most one of height and timestamp can be given
+     */
+    pub fn from_parts(height: Option<u64>, timestamp: Option<u64>) -> Result<Option<TimeLock>> {
+        match (height, timestamp) {
+            (Some(_), Some(_)) => Err(anyhow!("Output can be locked either until a height or until a timestamp, not both")),
+            (Some(height), None) => Ok(Some(TimeLock::Height(height))),
+            (None, Some(timestamp)) => Ok(Some(TimeLock::Timestamp(timestamp))),
+            (None, None) => Ok(None),
+        }
+    }
+
+    pub fn is_unlocked_at(&self, at: &BlockTime) -> bool {
+        match self {
+            TimeLock::Height(height) => at.height >= *height,
+            TimeLock::Timestamp(timestamp) => at.timestamp >= *timestamp,
+        }
+    }
+}
+
+impl Display for TimeLock {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TimeLock::Height(height) => write!(f, "height {}", height),
+            TimeLock::Timestamp(timestamp) => write!(f, "timestamp {}", timestamp),
+        }
     }
 }
 
@@ -61,4 +165,4 @@ impl TryFrom<&UnspentOutput> for Cbor {
         let cbor = serde_cbor::to_vec(value)?;
         Ok(Cbor(hex::encode(&cbor)))
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,285 @@
+use std::collections::{HashMap, HashSet, BTreeMap};
+
+use anyhow::{Result, anyhow};
+
+use crate::model::PublicKeyStr;
+
+use super::{asset::{AssetId, Issuance}, token::TokenMint, utxo::{UnspentOutput, OutPoint, BlockTime}, transaction_id::TransactionId, signed_balanced_transaction::{SignedBalancedTransaction, total_amount}, block::Block};
+
+/**
+ * Funds owned by an address
+ */
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Balance {
+    // spendable amount and number of spendable outputs
+    pub amount: u64,
+    pub utxos: usize,
+    // amount of outputs which can't be spent yet
+    pub locked: u64,
+    // spendable and locked amounts of other assets, the amounts above are in the native coin
+    pub assets: BTreeMap<AssetId, u64>,
+    pub locked_assets: BTreeMap<AssetId, u64>,
+}
+
+/**
+ * Amounts of the native coin
+ */
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Supply {
+    // held by unspent outputs which can be spent, and by those which can't be spent yet
+    pub circulating: u64,
+    pub locked: u64,
+    // created by the genesis allocations and the subsidies of all blocks, it includes fees left unclaimed by proposers
+    pub issued: u64,
+}
+
+/**
+ * Index of unspent outputs of the blockchain, of ids of committed transactions and of issued assets and minted tokens.
+ * It's updated every time a block is committed, so that looking up utxos doesn't require scanning the blockchain.
+ */
+#[derive(Default)]
+pub struct UtxoSet {
+    utxos: HashMap<OutPoint, UnspentOutput>,
+    // order in which unspent outputs were created
+    sequences: HashMap<OutPoint, u64>,
+    // unspent outputs owned by an address, oldest first
+    by_address: HashMap<PublicKeyStr, BTreeMap<u64, OutPoint>>,
+    next_sequence: u64,
+    // height of the block every committed transaction is in
+    committed: HashMap<TransactionId, u64>,
+    // every asset issued so far
+    assets: HashMap<AssetId, Issuance>,
+    // every token minted so far and the output holding it
+    tokens: HashMap<AssetId, TokenMint>,
+    token_outputs: HashMap<AssetId, OutPoint>,
+    // native coins created so far
+    issued: u64,
+}
+
+impl UtxoSet {
+    pub fn new(initial_utxos: &[UnspentOutput]) -> Self {
+        let mut utxo_set = UtxoSet::default();
+        for utxo in initial_utxos {
+            utxo_set.add(utxo);
+        }
+        // the genesis makes sure its allocations don't overflow
+        utxo_set.issued = initial_utxos.iter().filter(|utxo| utxo.asset.is_none()).map(|utxo| utxo.amount).sum();
+        utxo_set
+    }
+
+    pub fn get(&self, outpoint: &OutPoint) -> Option<&UnspentOutput> {
+        self.utxos.get(outpoint)
+    }
+
+    pub fn contains(&self, outpoint: &OutPoint) -> bool {
+        self.utxos.contains_key(outpoint)
+    }
+
+    pub fn len(&self) -> usize {
+        self.utxos.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.utxos.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &UnspentOutput> {
+        self.utxos.values()
+    }
+
+    /**
+     * Returns unspent outputs owned by the given address, oldest first
+     */
+    pub fn unspent_for<'a>(&'a self, address: &PublicKeyStr) -> impl Iterator<Item = &'a UnspentOutput> {
+        self.by_address.get(address)
+            .into_iter()
+            .flat_map(|outpoints| outpoints.values())
+            .filter_map(|outpoint| self.utxos.get(outpoint))
+    }
+
+    /**
+     * Returns unspent outputs owned by any of the given addresses, oldest first
+     */
+    pub fn unspent_for_all(&self, addresses: &[PublicKeyStr]) -> Vec<&UnspentOutput> {
+        let mut outpoints: Vec<(u64, &OutPoint)> = addresses.iter()
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .filter_map(|address| self.by_address.get(address))
+            .flat_map(|outpoints| outpoints.iter().map(|(sequence, outpoint)| (*sequence, outpoint)))
+            .collect();
+        outpoints.sort();
+        outpoints.into_iter().filter_map(|(_, outpoint)| self.utxos.get(outpoint)).collect()
+    }
+
+    /**
+     * Balance of the address for a block at the given time, outputs locked at that time are counted separately
+     */
+    pub fn balance(&self, address: &PublicKeyStr, at: &BlockTime) -> Result<Balance> {
+        let mut balance = Balance::default();
+        for utxo in self.unspent_for(address) {
+            let total = match (&utxo.asset, utxo.is_spendable_at(at)) {
+                (None, true) => {
+                    balance.utxos += 1;
+                    &mut balance.amount
+                },
+                (None, false) => &mut balance.locked,
+                (Some(asset), true) => balance.assets.entry(asset.clone()).or_default(),
+                (Some(asset), false) => balance.locked_assets.entry(asset.clone()).or_default(),
+            };
+            *total = total.checked_add(utxo.amount).ok_or(anyhow!("Balance of {} is too large", address))?;
+        }
+        Ok(balance)
+    }
+
+    /**
+     * Returns balances of all addresses owning at least one unspent output
+     */
+    pub fn balances(&self, at: &BlockTime) -> Result<Vec<(PublicKeyStr, Balance)>> {
+        self.by_address.keys().map(|address| Ok((address.clone(), self.balance(address, at)?))).collect()
+    }
+
+    /**
+     * Native coins in existence, outputs locked at the given time are counted separately
+     */
+    pub fn supply(&self, at: &BlockTime) -> Result<Supply> {
+        let mut supply = Supply { issued: self.issued, ..Supply::default() };
+        for utxo in self.iter().filter(|utxo| utxo.asset.is_none()) {
+            let total = match utxo.is_spendable_at(at) {
+                true => &mut supply.circulating,
+                false => &mut supply.locked,
+            };
+            *total = total.checked_add(utxo.amount).ok_or(anyhow!("Supply of the native coin is too large"))?;
+        }
+        Ok(supply)
+    }
+
+    /**
+     * Native coins created so far once `created` more are added, rewards never claim more than `issuable`
+     */
+    pub fn issued_with(&self, created: u64) -> Result<u64> {
+        self.issued.checked_add(created).ok_or(anyhow!("Issuing {} more coins exceeds the maximum supply, {} are issued already", created, self.issued))
+    }
+
+    /**
+     * Native coins which can still be created before the issued ones reach the maximum supply of u64
+     */
+    pub fn issuable(&self) -> u64 {
+        u64::MAX - self.issued
+    }
+
+    /**
+     * Makes sure given outputs exist and unspent, every output can only be used once
+     */
+    pub fn ensure_unspent(&self, outpoints: &[OutPoint]) -> Result<()> {
+        let mut seen = HashSet::new();
+        let mut not_found = Vec::new();
+
+        for outpoint in outpoints {
+            if !seen.insert(outpoint) {
+                return Err(anyhow!("Utxo {} is used more than once", outpoint));
+            }
+            if !self.contains(outpoint) {
+                not_found.push(outpoint.to_string());
+            }
+        }
+
+        if !not_found.is_empty() {
+            return Err(anyhow!("Utxos not found or already spent: {}", not_found.join(", ")));
+        }
+
+        Ok(())
+    }
+
+    /**
+     * Looks up outputs referenced by the given outpoints, they must be unspent
+     */
+    pub fn resolve(&self, outpoints: &[OutPoint]) -> Result<Vec<UnspentOutput>> {
+        self.ensure_unspent(outpoints)?;
+        Ok(outpoints.iter().filter_map(|outpoint| self.get(outpoint)).cloned().collect())
+    }
+
+    /**
+     * Returns issuance of the asset, if it's issued
+     */
+    pub fn asset(&self, asset: &AssetId) -> Option<&Issuance> {
+        self.assets.get(asset)
+    }
+
+    /**
+     * Returns mint of the token, if it's minted
+     */
+    pub fn token(&self, token: &AssetId) -> Option<&TokenMint> {
+        self.tokens.get(token)
+    }
+
+    /**
+     * Returns the output holding the token, its address is the owner of the token
+     */
+    pub fn token_output(&self, token: &AssetId) -> Option<&UnspentOutput> {
+        self.token_outputs.get(token).and_then(|outpoint| self.utxos.get(outpoint))
+    }
+
+    pub fn apply_transaction(&mut self, transaction: &SignedBalancedTransaction) {
+        if let Some(issuance) = &transaction.balanced_transaction.issuance {
+            self.assets.insert(issuance.asset_id(), issuance.clone());
+        }
+        if let (Some(token), Some(mint)) = (transaction.balanced_transaction.minted_token(), &transaction.balanced_transaction.mint) {
+            self.tokens.insert(token, mint.clone());
+        }
+        for outpoint in transaction.inputs() {
+            self.remove(outpoint);
+        }
+        for utxo in transaction.unspent_outputs() {
+            self.add(&utxo);
+        }
+    }
+
+    pub fn apply_block(&mut self, block: &Block) -> Result<()> {
+        // the reward creates what it claims above the fees, fees only move coins which exist already
+        if let (Some(reward), transactions) = block.split_reward() {
+            let fees = total_amount(transactions.iter()
+                .filter_map(|transaction| self.resolve(transaction.inputs()).and_then(|spent| transaction.fee(&spent)).ok()))?;
+            let claimed = total_amount(reward.outputs().iter().map(|output| output.amount))?;
+            self.issued = self.issued_with(claimed.saturating_sub(fees))?;
+        }
+        for transaction in &block.transactions {
+            self.apply_transaction(transaction);
+            self.committed.insert(transaction.id(), block.header.height);
+        }
+        Ok(())
+    }
+
+    /**
+     * Returns height of the block the transaction was committed in
+     */
+    pub fn height_of_transaction(&self, txid: &TransactionId) -> Option<u64> {
+        self.committed.get(txid).copied()
+    }
+
+    fn add(&mut self, utxo: &UnspentOutput) {
+        let sequence = self.next_sequence;
+        self.next_sequence += 1;
+        self.by_address.entry(utxo.address.clone()).or_default().insert(sequence, utxo.outpoint.clone());
+        self.sequences.insert(utxo.outpoint.clone(), sequence);
+        self.utxos.insert(utxo.outpoint.clone(), utxo.clone());
+        if let Some(token) = utxo.asset.as_ref().filter(|asset| self.tokens.contains_key(asset)) {
+            self.token_outputs.insert(token.clone(), utxo.outpoint.clone());
+        }
+    }
+
+    fn remove(&mut self, outpoint: &OutPoint) {
+        if let (Some(utxo), Some(sequence)) = (self.utxos.remove(outpoint), self.sequences.remove(outpoint)) {
+            if let Some(token) = &utxo.asset {
+                if self.token_outputs.get(token) == Some(outpoint) {
+                    self.token_outputs.remove(token);
+                }
+            }
+            if let Some(outpoints) = self.by_address.get_mut(&utxo.address) {
+                outpoints.remove(&sequence);
+                if outpoints.is_empty() {
+                    self.by_address.remove(&utxo.address);
+                }
+            }
+        }
+    }
+}
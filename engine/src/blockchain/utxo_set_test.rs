@@ -0,0 +1,45 @@
+#[cfg(test)]
+mod tests {
+    use crate::{blockchain::{transaction::Transaction, coin_selection::CoinSelection, blockchain::BlockChain, utxo::OutPoint, transaction_id::TransactionId, genesis::{Genesis, GenesisAllocation}, utxo_set::Balance}, encryption::generate_rsa_keypair_custom};
+
+    #[test]
+    fn utxo_set_follows_committed_blocks() {
+        let (validator_private_key, validator_public_key) = &generate_rsa_keypair_custom().unwrap();
+        let (priv_1, pub_1) = &generate_rsa_keypair_custom().unwrap();
+        let (_, pub_2) = &generate_rsa_keypair_custom().unwrap();
+        let genesis = Genesis::new("test", vec![GenesisAllocation::new(pub_1, 10)], Vec::new());
+        let initial_utxo = genesis.initial_utxos().remove(0).outpoint;
+
+        let mut blockchain = BlockChain::new(validator_public_key, genesis);
+        assert_eq!(blockchain.utxos().len(), 1, "Initial utxo is missing");
+        assert_eq!(blockchain.utxos().unspent_for(pub_1).count(), 1, "Initial utxo is not indexed by address");
+
+        let transaction = Transaction::new(pub_1, pub_2, 4)
+            .balance_transaction(&blockchain, 0, CoinSelection::default())
+            .unwrap()
+            .sign(&priv_1.try_into().unwrap())
+            .unwrap();
+        blockchain.commit_transaction(&transaction, validator_private_key).unwrap();
+
+        assert_eq!(blockchain.utxos().len(), 2, "Number of utxos is wrong");
+        assert!(!blockchain.utxos().contains(&initial_utxo), "Spent utxo is still in the set");
+        assert_eq!(blockchain.utxos().unspent_for(pub_1).map(|utxo| utxo.amount).sum::<u64>(), 6, "Change is wrong");
+        assert_eq!(blockchain.utxos().unspent_for(pub_2).map(|utxo| utxo.amount).sum::<u64>(), 4, "Transferred amount is wrong");
+
+        assert_eq!(blockchain.balance(pub_1).unwrap(), Balance { amount: 6, utxos: 1, ..Balance::default() }, "Balance of the sender is wrong");
+        assert_eq!(blockchain.balance(validator_public_key).unwrap(), Balance::default(), "Address without utxos must have zero balance");
+        let mut expected_balances = vec![(pub_1.clone(), 6), (pub_2.clone(), 4)];
+        expected_balances.sort_by(|(a, _), (b, _)| a.0.0.cmp(&b.0.0));
+        assert_eq!(blockchain.all_balances().unwrap(), expected_balances, "Balances are wrong");
+
+        let err = blockchain.ensure_utxos_unspent(std::slice::from_ref(&initial_utxo)).unwrap_err();
+        assert_eq!(err.to_string(), format!("Utxos not found or already spent: {}", initial_utxo));
+
+        let unknown_utxo = OutPoint::new(&TransactionId::new("unknown"), 0);
+        let err = blockchain.ensure_utxos_unspent(std::slice::from_ref(&unknown_utxo)).unwrap_err();
+        assert_eq!(err.to_string(), format!("Utxos not found or already spent: {}", unknown_utxo));
+
+        let change = blockchain.utxos().unspent_for(pub_1).next().unwrap().outpoint.clone();
+        assert!(blockchain.ensure_utxos_unspent(&[change.clone(), change]).is_err(), "The same utxo can't be spent twice in a transaction");
+    }
+}
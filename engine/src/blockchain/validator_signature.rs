@@ -1,9 +1,11 @@
+use anyhow::{Result, anyhow};
 use protocol::request::{ValidatorWithSignature, Validator};
-use serde::Serialize;
+use rsa::RsaPublicKey;
+use serde::{Serialize, Deserialize};
 
 use crate::model::{PublicKeyStr, Signature};
 
-#[derive(Clone, Serialize, PartialEq, Eq, Hash)]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub struct ValidatorSignature {
     pub validator_public_key: PublicKeyStr,
     pub validator_signature: Signature
@@ -13,6 +15,15 @@ impl ValidatorSignature {
     pub fn new(validator_public_key: &PublicKeyStr, validator_signature: &Signature) -> ValidatorSignature {
         ValidatorSignature { validator_public_key: validator_public_key.clone(), validator_signature: validator_signature.clone() }
     }
+
+    /**
+     * Makes sure the signature was made over the given block hash with the private key of the validator
+     */
+    pub fn verify(&self, block_hash: &str) -> Result<()> {
+        let public_key = RsaPublicKey::try_from(&self.validator_public_key)?;
+        self.validator_signature.verify(&public_key, &hex::decode(block_hash)?)
+            .map_err(|_| anyhow!("Signature of validator {} is invalid for block {}", self.validator_public_key, block_hash))
+    }
 }
 
 impl From<&ValidatorSignature> for ValidatorWithSignature {
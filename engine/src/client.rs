@@ -5,7 +5,7 @@ use log::trace;
 use protocol::{request::Request, request::{CommandResponse, Response, CommandRequest, ResponseBody}};
 use rsa::RsaPrivateKey;
 
-use crate::{model::PrivateKeyStr, blockchain::{cbor::Cbor, balanced_transaction::BalancedTransaction}};
+use crate::{model::{PrivateKeyStr, PublicKeyStr}, blockchain::{htlc::Htlc, cbor::Cbor, balanced_transaction::BalancedTransaction, utxo_set::{Balance, Supply}, coin_selection::CoinSelection, utxo::TimeLock, signed_balanced_transaction::Witness, asset::AssetId}};
 
 pub struct Client {
     destination: String,
@@ -28,6 +28,26 @@ impl Client {
         send_bytes(&self.destination, &CommandRequest::PrintBalances.to_client_request())
     }
 
+    pub fn balance(&self, address: &str) -> Result<Balance> {
+        let response = send_bytes(&self.destination, &CommandRequest::GetBalance { address: address.to_owned() }.to_client_request())?;
+        if let Response {body: ResponseBody::Success (CommandResponse::GetBalanceResponse { amount, utxos, locked, assets, locked_assets, .. }), ..} = response {
+            let assets = assets.iter().map(|(asset, amount)| (AssetId::new(asset), *amount)).collect();
+            let locked_assets = locked_assets.iter().map(|(asset, amount)| (AssetId::new(asset), *amount)).collect();
+            Ok(Balance { amount, utxos: utxos as usize, locked, assets, locked_assets })
+        } else {
+            Err(anyhow!("Unexpected response for balance: {:?}", response))
+        }
+    }
+
+    pub fn supply(&self) -> Result<Supply> {
+        let response = send_bytes(&self.destination, &CommandRequest::GetSupply.to_client_request())?;
+        if let Response {body: ResponseBody::Success (CommandResponse::GetSupplyResponse { circulating, locked, total_issued }), ..} = response {
+            Ok(Supply { circulating, locked, issued: total_issued })
+        } else {
+            Err(anyhow!("Unexpected response for supply: {:?}", response))
+        }
+    }
+
     pub fn print_validators(&self) -> Result<String> {
         let response = send_bytes(&self.destination, &CommandRequest::PrintValidators.to_client_request())?;
         if let Response {body: ResponseBody::Success (CommandResponse::PrintValidatorsResponse(response)), ..} = response {
@@ -38,20 +58,122 @@ impl Client {
         }
     }
 
-    pub fn balance_transaction(&self, from: &str, to: &str, amount: u64) -> Result<Response> {
-        send_bytes(&self.destination, &CommandRequest::new_balance_transaction(from, to, amount).to_client_request())
+    /**
+     * Outputs paid to the recipients can't be spent until the lock expires.
+     * Recipients are paid in `asset`, or in a new asset named `issue` issued by the first sender, the native coin if both are None.
+     */
+    #[allow(clippy::too_many_arguments)]
+    pub fn balance_transaction(&self, from: &[&str], to: &[(&str, u64)], fee_rate: u64, coin_selection: CoinSelection, lock: Option<TimeLock>, asset: Option<&str>, issue: Option<&str>) -> Result<Response> {
+        let (lock_height, lock_timestamp) = match lock {
+            Some(TimeLock::Height(height)) => (Some(height), None),
+            Some(TimeLock::Timestamp(timestamp)) => (None, Some(timestamp)),
+            None => (None, None),
+        };
+        send_bytes(&self.destination, &CommandRequest::new_balance_transaction(from, to, fee_rate, &coin_selection.to_string(), lock_height, lock_timestamp, asset, issue).to_client_request())
     }
 
-    pub fn commit_transaction(&self, cbor: &str, private_key: &str) -> Result<Response> {
-        let rsa_private_key = RsaPrivateKey::try_from(&PrivateKeyStr(private_key.to_string()))?;
-        let balanced_transaction = BalancedTransaction::try_from(&Cbor::new(cbor))?;
-
-        let signed_transaction = balanced_transaction.sign(&rsa_private_key)?;
-        let signed_cbor: Cbor = (&signed_transaction).try_into()?;
+    /**
+     * Signs the transaction by every owner of its inputs and commits it, private keys of all owners must be given.
+     * Witness reveals policies of the multisig and script addresses the transaction spends from.
+     */
+    pub fn commit_transaction(&self, cbor: &str, private_keys: &[&str], witness: &Witness) -> Result<Response> {
+        let signed_cbor = sign_transaction(cbor, private_keys, witness)?;
     
         send_bytes(&self.destination, &CommandRequest::new_commit_transaction(&signed_cbor.0).to_client_request())
     }
 
+    /**
+     * Signs all transactions and commits them in a single block, the transactions may spend funds of different owners.
+     * Every transaction is signed only by those of the given keys which own its inputs and reveals only the policies it spends from.
+     */
+    pub fn commit_transactions(&self, cbors: &[&str], private_keys: &[&str], witness: &Witness) -> Result<Response> {
+        let rsa_private_keys = rsa_private_keys(private_keys)?;
+        let mut signed_cbors = Vec::new();
+        for cbor in cbors {
+            let balanced_transaction = BalancedTransaction::try_from(&Cbor::new(cbor))?;
+            let owners = self.input_owners(cbor)?;
+            let signed_transaction = balanced_transaction.sign_by_owners(&owners, &rsa_private_keys, witness)?;
+            signed_cbors.push(Cbor::try_from(&signed_transaction)?.0);
+        }
+
+        send_bytes(&self.destination, &CommandRequest::new_commit_transactions(&signed_cbors).to_client_request())
+    }
+
+    /**
+     * Owners of the outputs spent by the balanced transaction, in the order of its inputs
+     */
+    pub fn input_owners(&self, cbor: &str) -> Result<Vec<PublicKeyStr>> {
+        let response = send_bytes(&self.destination, &CommandRequest::GetInputOwners { cbor: cbor.to_owned() }.to_client_request())?;
+        if let Response {body: ResponseBody::Success (CommandResponse::GetInputOwnersResponse { owners }), ..} = response {
+            Ok(owners.iter().map(|owner| PublicKeyStr::from_str(owner)).collect())
+        } else {
+            Err(anyhow!("Unexpected response for input owners: {:?}", response))
+        }
+    }
+
+    /**
+     * Locks `amount` of the sender in the contract, the private key of the sender must be given
+     */
+    pub fn create_htlc(&self, htlc: &Htlc, amount: u64, fee_rate: u64, private_key: &str) -> Result<Response> {
+        let cbor = self.balanced_cbor(&htlc.sender, &htlc.address(), amount, fee_rate)?;
+        self.commit_transaction(&cbor, &[private_key], &Witness::default())
+    }
+
+    /**
+     * Pays all funds of the contract to the recipient, possible before the deadline only.
+     * The preimage is hex encoded, the private key of the recipient must be given.
+     */
+    pub fn claim_htlc(&self, htlc: &Htlc, preimage: &str, fee_rate: u64, private_key: &str) -> Result<Response> {
+        let witness = htlc.claim_witness(preimage)?;
+        self.spend_htlc(htlc, &htlc.recipient, &witness, fee_rate, private_key)
+    }
+
+    /**
+     * Pays all funds of the contract back to the sender, possible once the deadline passed.
+     * The private key of the sender must be given.
+     */
+    pub fn refund_htlc(&self, htlc: &Htlc, fee_rate: u64, private_key: &str) -> Result<Response> {
+        self.spend_htlc(htlc, &htlc.sender, &htlc.refund_witness(), fee_rate, private_key)
+    }
+
+    fn spend_htlc(&self, htlc: &Htlc, to: &PublicKeyStr, witness: &Witness, fee_rate: u64, private_key: &str) -> Result<Response> {
+        let address = htlc.address();
+        let balance = self.balance(&address.0.0)?;
+        // every utxo of the contract is spent and there is no change
+        let fee = fee_rate.checked_mul(balance.utxos as u64 + 1).ok_or(anyhow!("Fee of the transaction is too large"))?;
+        let amount = balance.amount.checked_sub(fee).filter(|amount| *amount > 0)
+            .ok_or(anyhow!("Contract {} holds {} which does not cover the fee {}", address, balance.amount, fee))?;
+
+        let cbor = self.balanced_cbor(&address, to, amount, fee_rate)?;
+        self.commit_transaction(&cbor, &[private_key], witness)
+    }
+
+    fn balanced_cbor(&self, from: &PublicKeyStr, to: &PublicKeyStr, amount: u64, fee_rate: u64) -> Result<String> {
+        cbor_of(self.balance_transaction(&[&from.0.0], &[(&to.0.0, amount)], fee_rate, CoinSelection::default(), None, None, None)?)
+    }
+
+    /**
+     * Mints a token with hex encoded metadata to the recipient, the private key of the minter must be given.
+     * Returns id of the token along with the response to the commit.
+     */
+    pub fn mint_token(&self, from: &str, to: &str, metadata: &str, fee_rate: u64, private_key: &str) -> Result<(AssetId, Response)> {
+        let cbor = cbor_of(send_bytes(&self.destination, &CommandRequest::new_mint_token(from, to, metadata, fee_rate).to_client_request())?)?;
+        let token_id = BalancedTransaction::try_from(&Cbor::new(&cbor))?.minted_token().ok_or(anyhow!("Transaction mints no token"))?;
+        Ok((token_id, self.commit_transaction(&cbor, &[private_key], &Witness::default())?))
+    }
+
+    /**
+     * Transfers the token to the recipient, the private key of its owner must be given
+     */
+    pub fn transfer_token(&self, from: &str, to: &str, token_id: &str, fee_rate: u64, private_key: &str) -> Result<Response> {
+        let cbor = cbor_of(send_bytes(&self.destination, &CommandRequest::new_transfer_token(from, to, token_id, fee_rate).to_client_request())?)?;
+        self.commit_transaction(&cbor, &[private_key], &Witness::default())
+    }
+
+    pub fn token_owner(&self, token_id: &str) -> Result<Response> {
+        send_bytes(&self.destination, &CommandRequest::GetTokenOwner { token_id: token_id.to_owned() }.to_client_request())
+    }
+
     pub fn print_blockchain(&self) -> Result<String> {
         let response = send_bytes(&self.destination, &CommandRequest::PrintBlockchain.to_client_request())?;
         if let Response {body: ResponseBody::Success (CommandResponse::PrintBlockchainResponse{blocks}), ..} = response {
@@ -60,6 +182,43 @@ impl Client {
             Err(anyhow!("Unexpected response for print_blockchain: {:?}", response))
         }
     }
+
+    pub fn audit_blockchain(&self) -> Result<String> {
+        let response = send_bytes(&self.destination, &CommandRequest::AuditBlockchain.to_client_request())?;
+        if let Response {body: ResponseBody::Success (CommandResponse::AuditBlockchainResponse{blocks, corrupted_height, reason}), ..} = response {
+            match (corrupted_height, reason) {
+                (Some(height), Some(reason)) => Ok(format!("Blockchain corrupted at height {} of {}: {}", height, blocks, reason)),
+                _ => Ok(format!("All {} blocks verified", blocks)),
+            }
+        } else {
+            Err(anyhow!("Unexpected response for audit_blockchain: {:?}", response))
+        }
+    }
+}
+
+/**
+ * Extracts the balanced transaction from the response to a balancing command
+ */
+fn cbor_of(response: Response) -> Result<String> {
+    if let Response {body: ResponseBody::Success (CommandResponse::BalanceTransactionResponse { cbor, .. }), ..} = response {
+        Ok(cbor)
+    } else {
+        Err(anyhow!("Unexpected response for balance_transaction: {:?}", response))
+    }
+}
+
+fn rsa_private_keys(private_keys: &[&str]) -> Result<Vec<RsaPrivateKey>> {
+    private_keys.iter()
+        .map(|private_key| RsaPrivateKey::try_from(&PrivateKeyStr(private_key.to_string())))
+        .collect()
+}
+
+fn sign_transaction(cbor: &str, private_keys: &[&str], witness: &Witness) -> Result<Cbor> {
+    let rsa_private_keys = rsa_private_keys(private_keys)?;
+    let balanced_transaction = BalancedTransaction::try_from(&Cbor::new(cbor))?;
+
+    let signed_transaction = balanced_transaction.sign_with(&rsa_private_keys)?.with_witness(witness.clone());
+    (&signed_transaction).try_into()
 }
 
 pub fn send_bytes_n_attempts(attempts: u8, destination: &str, msg: Request) -> Result<Response> {
@@ -86,8 +245,8 @@ pub fn send_bytes(destination: &str, msg: &Request) -> Result<Response> {
 
     let bytes = serde_cbor::to_vec(&msg)?;
     let len: [u8; 8] = bytes.len().to_be_bytes();
-    stream.write(&len)?;
-    stream.write(&bytes)?;
+    stream.write_all(&len)?;
+    stream.write_all(&bytes)?;
 
     let response: Response = serde_cbor::from_reader(&stream)?;
 
@@ -1,8 +1,9 @@
+use anyhow::{Result, anyhow};
 use log::debug;
 use protocol::request::Validator;
 use rsa::RsaPrivateKey;
 
-use crate::model::{PrivateKeyStr, PublicKeyStr};
+use crate::{model::{PrivateKeyStr, PublicKeyStr}, blockchain::signed_balanced_transaction::SignedBalancedTransaction};
 
 #[derive(Clone)]
 pub struct ValidatorReference { 
@@ -19,6 +20,10 @@ pub struct Configuration {
     pub validator_private_key: PrivateKeyStr,
     pub validator_public_key: PublicKeyStr,
     pub validators: Vec<ValidatorReference>,
+    // validators listed in the genesis, they are trusted even before they join
+    pub genesis_validators: Vec<PublicKeyStr>,
+    // minimum fee per input and output of transactions this node commits
+    pub min_fee_rate: u64,
 }
 
 impl Configuration {
@@ -30,7 +35,9 @@ impl Configuration {
             port,
             validator_private_key: validator_private_key.clone(),
             validator_public_key: public_key,
-            validators: Vec::new()
+            validators: Vec::new(),
+            genesis_validators: Vec::new(),
+            min_fee_rate: 0,
         }
     }
 
@@ -59,6 +66,33 @@ impl Configuration {
         })
     }
 
+    /**
+     * Returns true if the key belongs to this validator, one of the validators it knows about or a genesis validator
+     */
+    pub fn is_known_validator(&self, key: &PublicKeyStr) -> bool {
+        *key == self.validator_public_key
+            || self.validators.iter().any(|validator| validator.pk == *key)
+            || self.genesis_validators.contains(key)
+    }
+
+    pub fn ensure_known_validator(&self, key: &PublicKeyStr) -> Result<()> {
+        if !self.is_known_validator(key) {
+            return Err(anyhow!("Validator {} is unknown, its signature is rejected", key));
+        }
+        Ok(())
+    }
+
+    /**
+     * Makes sure the transaction pays at least the minimum fee of this node
+     */
+    pub fn ensure_min_fee(&self, transaction: &SignedBalancedTransaction, fee: u64) -> Result<()> {
+        let min_fee = self.min_fee_rate.saturating_mul(transaction.weight());
+        if fee < min_fee {
+            return Err(anyhow!("Transaction pays fee {}, but at least {} is required", fee, min_fee));
+        }
+        Ok(())
+    }
+
     pub fn validator_ref(&self) -> ValidatorReference {
         ValidatorReference { pk: self.validator_public_key.clone(), address: ValidatorAddress(format!("{}:{}", self.ip, self.port)) }
     }
@@ -1,37 +1,47 @@
 use crate::{
     configuration::{Configuration, ValidatorAddress, ValidatorReference},
     model::{PublicKeyStr, PrivateKeyStr},
-    request_handlers::handle_request, blockchain::{blockchain::BlockChain, utxo::UnspentOutput}, client::send_bytes, response_handlers::handle_response,
+    request_handlers::handle_request, blockchain::{blockchain::BlockChain, genesis::Genesis}, client::send_bytes, response_handlers::handle_response,
 };
-use anyhow::Result;
+use anyhow::{Result, anyhow};
 use futures::{future::lazy, channel::oneshot::{self, Sender}, FutureExt};
 use log::{info, debug, trace, error};
 use protocol::{request::{Request, CommandRequest}, request::{Response, ResponseBody, CommandResponse}};
 use rsa::{RsaPublicKey, RsaPrivateKey};
 use tokio::{net::{TcpListener, TcpStream}, io::{AsyncReadExt, AsyncWriteExt}};
 use std::{
-    sync::{Mutex, Arc, mpsc}, collections::HashSet,
+    sync::{Mutex, Arc, mpsc}, collections::HashSet, path::Path,
 };
 
+/**
+ * Largest request accepted from a peer, longer frames are rejected before their body is read
+ */
+pub const MAX_MESSAGE_BYTES: usize = 16 * 1024 * 1024;
 
-pub async fn run_node(host: String, port: u16, remote_validator_opt: Option<&str>, private_key: &str, public_key: &str) -> Result<()> {
+#[allow(clippy::too_many_arguments)]
+pub async fn run_node(host: String, port: u16, remote_validator_opt: Option<&str>, private_key: &str, public_key: &str, genesis_path: &Path, data_dir: Option<&Path>, min_fee_rate: u64) -> Result<()> {
     let listener = TcpListener::bind(format!("{}:{}", host, port)).await?;
 
     info!("Validator node is running on {}:{}", host, port);
-   
-    // a hardcoded public key for the initial block
-    let pub_key_str = PublicKeyStr::from_str("3082010a0282010100bae507cd770270df4e249dfde2a89fe9f6abccbb2e56a82f7ce370c763355c09b596d14734d9e225c3ee913f442aa75ea3dba35edb0ae20bdac52ab8f5451c2dafb93a59dccef395f2dce4069880d8ac1f25300edd09fe61cfe0734efb789fc0c8d8d9f1f916165713f394fc275c2652c69fdbddd43e14b12971683e918dcfb0b97511cb36132acb156235d93aac5f3b46b7ae10445c757ed3ebc6c81c9ae8d496e2ecf948c70a100a10badc68558d121a1240df756c55c8c4c90990c826646dec4e319b55ce15c1e24d9273ea560aeb09834caa0827f99668e81d865a12e059ddaf5987601a7d6c5bfaf14e72182eb83369883a01f9eeb4b09261f7a1c148190203010001");
-    let pub_key = RsaPublicKey::try_from(&pub_key_str)?;
+
+    let genesis = Genesis::load(genesis_path)?;
+    info!("Genesis of chain {} loaded, {} allocations, {} validators", genesis.chain_id, genesis.allocations.len(), genesis.validators.len());
+    let chain_id = genesis.chain_id.to_owned();
 
     let validator_private_key = PrivateKeyStr::from_str(private_key);
     let validator_public_key = PublicKeyStr::from_str(public_key);
     RsaPublicKey::try_from(&validator_public_key).expect("Public key provided is wrong");
     RsaPrivateKey::try_from(&validator_private_key).expect("Private key provided is wrong");
 
-    let configuration = Configuration::new(&host, port, &validator_private_key);
+    let mut configuration = Configuration::new(&host, port, &validator_private_key);
+    configuration.genesis_validators = genesis.validators.clone();
+    configuration.min_fee_rate = min_fee_rate;
     let validator = configuration.validator();
     let configuration = Arc::new(Mutex::new(configuration));
-    let blockchain = BlockChain::new(&validator_public_key, UnspentOutput::initial_utxo(&pub_key_str, 100));
+    let blockchain = match data_dir {
+        Some(data_dir) => BlockChain::open(&validator_public_key, genesis, data_dir)?,
+        None => BlockChain::new(&validator_public_key, genesis),
+    };
     let blockchain = Arc::new(Mutex::new(blockchain));
 
     let processed_requests = Arc::new(Mutex::new(HashSet::<String>::new()));
@@ -42,7 +52,7 @@ pub async fn run_node(host: String, port: u16, remote_validator_opt: Option<&str
     // Register current validator with other validators
     if let Some(remote_validator) = remote_validator_opt {
         info!("Connecting to remote validator {}", remote_validator);
-        let request = CommandRequest::new_on_board_command(&format!("{}:{}", host, port), &validator_public_key.0.0).to_request(&validator);
+        let request = CommandRequest::new_on_board_command(&format!("{}:{}", host, port), &validator_public_key.0.0, &chain_id).to_request(&validator);
 
         requests_sender.send((
             ValidatorReference { pk: PublicKeyStr::from_str("not-necessary-here"), address: ValidatorAddress(remote_validator.to_owned()) }, 
@@ -117,7 +127,7 @@ pub async fn run_node(host: String, port: u16, remote_validator_opt: Option<&str
                 };
 
                 let result = serde_cbor::to_vec(&response).unwrap();
-                socket.write(&result).await.unwrap();
+                socket.write_all(&result).await.unwrap();
             });
         }
     });
@@ -156,12 +166,15 @@ pub async fn run_node(host: String, port: u16, remote_validator_opt: Option<&str
  */
 async fn receive_and_parse(stream: &mut TcpStream) -> Result<Request> {
     let mut len: [u8; 8] = [0; 8];
-    let mut buf: [u8; 10240] = [0; 10240];
-    stream.read(&mut len).await?;
+    stream.read_exact(&mut len).await?;
 
-    let size_received = usize::from_be_bytes(len);
-    stream.read(&mut buf).await?;
+    let size_received = u64::from_be_bytes(len);
+    if size_received > MAX_MESSAGE_BYTES as u64 {
+        return Err(anyhow!("Message of {} bytes exceeds the limit of {} bytes", size_received, MAX_MESSAGE_BYTES));
+    }
+    let mut buf = vec![0; size_received as usize];
+    stream.read_exact(&mut buf).await?;
 
-    let received_msg: Request = serde_cbor::from_slice(&buf[0..size_received])?;
+    let received_msg: Request = serde_cbor::from_slice(&buf)?;
     Ok(received_msg)
 }
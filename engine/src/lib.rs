@@ -10,6 +10,7 @@ pub mod encryption;
 pub mod blockchain;
 pub mod utils;
 pub mod request_handlers;
+mod request_handlers_test;
 pub mod response_handlers;
 // pub mod circuits;
 
@@ -6,6 +6,7 @@ use super::HexString;
 pub struct PrivateKeyStr(pub String);
 
 impl PrivateKeyStr {
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str(str: &str) -> Self {
         PrivateKeyStr(str.to_owned())
     }
@@ -18,6 +18,7 @@ impl Display for PublicKeyStr {
 }
 
 impl PublicKeyStr {
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str(str: &str) -> Self {
         PublicKeyStr(HexString(str.to_owned()))
     }
@@ -1,12 +1,14 @@
+use std::str::FromStr;
+
 use log::{info, trace, debug, error};
 use protocol::{
-    request::{CommandResponse, CommandRequest, Validator, ValidatorWithSignature, self, Response}, request::{Request, ResponseBody, _PrintValidatorsResponse},
+    request::{CommandResponse, CommandRequest, Validator, self, Response}, request::{Request, ResponseBody, _PrintValidatorsResponse},
 };
 
 use crate::{
     configuration::{Configuration, ValidatorAddress, ValidatorReference},
     encryption::generate_rsa_key_pair,
-    model::{HexString, PublicKeyStr, Signature}, blockchain::{blockchain::BlockChain, transaction::Transaction, signed_balanced_transaction::{SignedBalancedTransaction}, cbor::Cbor, validator_signature::ValidatorSignature}, utils::shorten_long_string,
+    model::{HexString, PublicKeyStr}, blockchain::{blockchain::BlockChain, transaction::Transaction, signed_balanced_transaction::{SignedBalancedTransaction}, cbor::Cbor, validator_signature::ValidatorSignature, block::Block, coin_selection::CoinSelection, utxo::TimeLock, asset::AssetId, token::TokenMint, balanced_transaction::BalancedTransaction}, utils::shorten_long_string,
 };
 use anyhow::{Result, anyhow};
 
@@ -21,16 +23,22 @@ pub fn handle_request(
     };
 
     match &request.command {
-        CommandRequest::OnBoardValidator { return_address: new_validator_address, public_key: new_validator_public_key } => {
+        CommandRequest::OnBoardValidator { return_address: new_validator_address, public_key: new_validator_public_key, chain_id } => {
+            if *chain_id != blockchain.genesis.chain_id {
+                let msg = format!("Validator {} belongs to chain {}, but this is chain {}", new_validator_address, chain_id, blockchain.genesis.chain_id);
+                error!("{}", msg);
+                return err(&request.request_id, configuration.validator(), &msg);
+            }
+
             let mut requests = Vec::new();
 
             for validator in &configuration.validators {
-                let request = request::CommandRequest::new_on_board_command(&new_validator_address, &new_validator_public_key).to_request_with_id(configuration.validator(), &request.request_id);
+                let request = request::CommandRequest::new_on_board_command(new_validator_address, new_validator_public_key, chain_id).to_request_with_id(configuration.validator(), &request.request_id);
                 requests.push((validator.clone(), request));
             }
 
             let validator_address = ValidatorAddress(new_validator_address.to_owned());
-            configuration.add_validators(&[ValidatorReference { pk: PublicKeyStr::from_str(&new_validator_public_key), address: validator_address } ]);
+            configuration.add_validators(&[ValidatorReference { pk: PublicKeyStr::from_str(new_validator_public_key), address: validator_address } ]);
             info!(
                 "Added new validator {:?}, total validators {}",
                 new_validator_address,
@@ -52,9 +60,10 @@ pub fn handle_request(
                 orig_request_id: request.request_id.to_owned(),
                 replier: configuration.validator(),
                 body: ResponseBody::Success(CommandResponse::OnBoardValidatorResponse { 
+                    chain_id: blockchain.genesis.chain_id.to_owned(),
                     on_boarding_validator: Validator { address: configuration.address(), public_key: String::from(&configuration.validator_public_key) },
                     validators: all_validators, 
-                    blockchain_tip: blockchain.blockchain_hash()?
+                    blockchain_tip: blockchain.blockchain_hash()
                 }),
             };
             ok_with_requests(response, requests)
@@ -78,139 +87,176 @@ pub fn handle_request(
         CommandRequest::PrintBalances => {
             let balances =
                     blockchain
-                        .all_balances()
+                        .all_balances()?
                         .iter()
-                        .map(|(k, v)| (shorten_long_string(&k.0 .0), v.clone()))
+                        .map(|(k, v)| (shorten_long_string(&k.0 .0), *v))
                         .collect();
+            let locked = blockchain.all_locked_balances()?.iter().map(|(k, v)| (shorten_long_string(&k.0 .0), *v)).collect();
+            let assets = blockchain.all_asset_balances()?.iter().map(|(k, asset, v)| (shorten_long_string(&k.0 .0), asset.to_string(), *v)).collect();
+            let locked_assets = blockchain.all_locked_asset_balances()?.iter().map(|(k, asset, v)| (shorten_long_string(&k.0 .0), asset.to_string(), *v)).collect();
 
-            success(&request.request_id, configuration.validator(), CommandResponse::PrintBalancesResponse { balances })
+            success(&request.request_id, configuration.validator(), CommandResponse::PrintBalancesResponse { balances, locked, assets, locked_assets })
         },
         
-        CommandRequest::BalanceTransaction { from, to, amount } => {
-            let balanced_transaction = &Transaction::new(&PublicKeyStr::from_str(from), &PublicKeyStr::from_str(to), *amount)
-                .balance_transaction(&blockchain)?;
-            let cbor_bytes = balanced_transaction.to_cbor()?;
-            let cbor = hex::encode(&cbor_bytes);
-            let body = serde_json::to_string_pretty(balanced_transaction)?;
-
-            success(&request.request_id, configuration.validator(), CommandResponse::BalanceTransactionResponse { request_id: request.request_id.clone(), body, cbor })
+        CommandRequest::GetBalance { address } => {
+            let balance = blockchain.balance(&PublicKeyStr::from_str(address))?;
+            let assets = balance.assets.iter().map(|(asset, amount)| (asset.to_string(), *amount)).collect();
+            let locked_assets = balance.locked_assets.iter().map(|(asset, amount)| (asset.to_string(), *amount)).collect();
+            _success(CommandResponse::GetBalanceResponse { address: address.to_owned(), amount: balance.amount, utxos: balance.utxos as u64, locked: balance.locked, assets, locked_assets })
         },
 
-        CommandRequest::CommitTransaction { signed_transaction_cbor } => {
-            let blockchain_previous_tip = blockchain.blockchain_hash()?;
-            let signed_transaction = SignedBalancedTransaction::try_from(&Cbor::new(&signed_transaction_cbor))?;
-            let block = signed_transaction.commit(blockchain, &configuration.validator_private_key)?;
-            let validator_signature = block.validator_signatures().first().unwrap();
+        CommandRequest::BalanceTransaction { from, to, fee_rate, coin_selection, lock_height, lock_timestamp, asset, issue } => {
+            let from: Vec<PublicKeyStr> = from.iter().map(|address| PublicKeyStr::from_str(address)).collect();
+            let to: Vec<(PublicKeyStr, u64)> = to.iter().map(|(address, amount)| (PublicKeyStr::from_str(address), *amount)).collect();
+            let balanced_transaction = &Transaction::with_recipients(&from, &to)
+                .with_lock(TimeLock::from_parts(*lock_height, *lock_timestamp)?)
+                .with_asset(asset.as_deref().map(AssetId::new))
+                .with_issuance(issue.clone())
+                .balance_transaction(blockchain, (*fee_rate).max(configuration.min_fee_rate), CoinSelection::from_str(coin_selection)?)?;
+            balance_transaction_response(request, configuration, balanced_transaction)
+        },
 
-            let mut requests = Vec::new();
+        CommandRequest::MintToken { from, to, metadata, fee_rate } => {
+            let balanced_transaction = &Transaction::new(&PublicKeyStr::from_str(from), &PublicKeyStr::from_str(to), 1)
+                .with_mint(Some(TokenMint::new(metadata)?))
+                .balance_transaction(blockchain, (*fee_rate).max(configuration.min_fee_rate), CoinSelection::default())?;
+            balance_transaction_response(request, configuration, balanced_transaction)
+        },
 
-            for validator in &configuration.validators {
-                let request = CommandRequest::RequestTransactionValidation {
-                    blockchain_previous_tip: blockchain_previous_tip.to_owned(),
-                    blockchain_new_tip: block.hash.to_owned(),
-                    transaction_cbor: signed_transaction_cbor.to_owned(),
-                    validator_signature: ValidatorWithSignature {
-                        validator: Validator {
-                            address: configuration.address(), 
-                            public_key: configuration.validator_public_key.0.0.to_owned()
-                        },
-                        signature: validator_signature.validator_signature.0.0.to_owned()
-                    },
-                    validator: Validator { address: configuration.address(), public_key: configuration.validator_public_key.0.0.to_owned() },
-                }.to_request(&configuration.validator());
+        CommandRequest::TransferToken { from, to, token_id, fee_rate } => {
+            let token_id = AssetId::new(token_id);
+            if blockchain.utxos().token(&token_id).is_none() {
+                return err(&request.request_id, configuration.validator(), &format!("Token {} is not minted", token_id));
+            }
+            let balanced_transaction = &Transaction::new(&PublicKeyStr::from_str(from), &PublicKeyStr::from_str(to), 1)
+                .with_asset(Some(token_id))
+                .balance_transaction(blockchain, (*fee_rate).max(configuration.min_fee_rate), CoinSelection::default())?;
+            balance_transaction_response(request, configuration, balanced_transaction)
+        },
 
-                requests.push((validator.clone(), request));
+        CommandRequest::GetSupply => {
+            let supply = blockchain.supply()?;
+            _success(CommandResponse::GetSupplyResponse { circulating: supply.circulating, locked: supply.locked, total_issued: supply.issued })
+        },
+
+        CommandRequest::GetTokenOwner { token_id } => {
+            let token_id = AssetId::new(token_id);
+            match blockchain.utxos().token(&token_id).zip(blockchain.utxos().token_output(&token_id)) {
+                Some((mint, output)) => _success(CommandResponse::GetTokenOwnerResponse {
+                    token_id: token_id.to_string(),
+                    owner: output.address.0.0.to_owned(),
+                    outpoint: output.outpoint.to_string(),
+                    metadata: mint.metadata.to_owned(),
+                }),
+                None => err(&request.request_id, configuration.validator(), &format!("Token {} is not minted", token_id)),
             }
+        },
 
-            trace!("{}", serde_json::to_string_pretty(&block)?);
+        CommandRequest::GetInputOwners { cbor } => {
+            let transaction = BalancedTransaction::try_from(&Cbor::new(cbor))?;
+            let spent = blockchain.utxos().resolve(&transaction.inputs)?;
+            _success(CommandResponse::GetInputOwnersResponse { owners: spent.iter().map(|utxo| utxo.address.0.0.to_owned()).collect() })
+        },
 
-            let response = Response {
-                orig_request_id: request.request_id.to_owned(),
-                replier: configuration.validator(),
-                body: ResponseBody::Success (
-                    CommandResponse::CommitTransactionResponse { blockchain_hash: block.hash.to_owned() },
-                ),
-            };
-            ok_with_requests(response, requests)
+        CommandRequest::CommitTransaction { signed_transaction_cbor } => {
+            commit_transactions(request, blockchain, configuration, std::slice::from_ref(signed_transaction_cbor))
+        },
+
+        CommandRequest::CommitTransactions { signed_transactions_cbor } => {
+            commit_transactions(request, blockchain, configuration, signed_transactions_cbor)
         },
 
-        CommandRequest::RequestTransactionValidation { blockchain_previous_tip, blockchain_new_tip, transaction_cbor, validator_signature: sender_validator_signature, validator } => {
-            let blockchain_hash = blockchain.blockchain_hash()?;
+        CommandRequest::RequestTransactionValidation { blockchain_previous_tip, blockchain_new_tip, block_cbor } => {
+            let blockchain_hash = blockchain.blockchain_hash();
             if *blockchain_previous_tip != blockchain_hash {
                 let msg = format!("Transaction can't be applied for blockchains are not in sync: {} != {}", blockchain_previous_tip, blockchain_hash);
                 error!("{}", msg);
                 return err(&request.request_id, configuration.validator(), &msg);
             }
 
-            let signed_transaction = SignedBalancedTransaction::try_from(&Cbor::new(&transaction_cbor))?;
-            let block = signed_transaction.commit(blockchain, &configuration.validator_private_key)?;
-            let blockchain_hash = blockchain.blockchain_hash()?;
-            let validator_signature = block.validator_signatures().first().ok_or(anyhow!("Transaction wasn't signed by validator"))?;
-
+            let block = Block::try_from(&Cbor::new(block_cbor))?;
             if *blockchain_new_tip != block.hash {
-                let msg = format!("Blockchain hash is different. Possibility of a hard fork");
+                let msg = "Blockchain hash is different. Possibility of a hard fork".to_string();
                 error!("{}", msg);
                 return err(&request.request_id, configuration.validator(), &msg);
             }
 
-            debug!("Transaction successfully verified and added to blockchain. Total verifications: {}", block.validator_signatures().len());
-            trace!("{}", serde_json::to_string_pretty(&block)?);
+            for signature in block.validator_signatures() {
+                configuration.ensure_known_validator(&signature.validator_public_key)?;
+            }
 
-            // Add signature from the sender validator into the block
-            let last = blockchain.blocks.last_mut().unwrap();
-            last.add_validator_signature(
-                ValidatorSignature::new(
-                    &PublicKeyStr::from_str(&validator.public_key), 
-                    &Signature::from_string(&sender_validator_signature.signature)
-                )
-            );
+            let block = blockchain.append_block(block, &configuration.validator_private_key)?;
+            let validator_signature = block.signature_of(&configuration.validator_public_key).ok_or(anyhow!("Block wasn't signed by validator"))?;
+
+            debug!("Block {} successfully verified and added to blockchain. Total verifications: {}", block.header.height, block.validator_signatures().len());
+            trace!("{}", serde_json::to_string_pretty(&block)?);
 
             success(&request.request_id, configuration.validator(), 
                 CommandResponse::RequestTransactionValidationResponse {
-                    new_blockchain_tip: blockchain_hash,
+                    new_blockchain_tip: block.hash.to_owned(),
                     validator_public_key: configuration.validator_public_key.0.0.to_owned(),
-                    transaction_cbor: transaction_cbor.to_owned(),
+                    height: block.header.height,
                     validator_signature: validator_signature.validator_signature.0.0.to_owned(),
                     old_blockchain_tip: blockchain_previous_tip.to_owned(),
                 },
             )
         },
         
-        CommandRequest::SynchronizeBlockchain { signatures, transaction_cbor, blockchain_tip_before_transaction, blockchain_tip_after_transaction  } => {
+        CommandRequest::SynchronizeBlockchain { signatures, height, blockchain_tip_before_transaction, blockchain_tip_after_transaction  } => {
             debug!("Synchronization request received");
-            let last = blockchain.blocks.last_mut().unwrap();
-
-            if last.hash != *blockchain_tip_after_transaction {
-                return err(&request.request_id, configuration.validator(), &format!("Blockchain tips are different, synchronization needed. Incoming tip: {}, this blockchain tip: {}", blockchain_tip_after_transaction, last.hash));
+            if signatures.len() != 1 {
+                return err(&request.request_id, configuration.validator(), &format!("Exactly one signature is supported by SynchronizeBlockchain for now, received {}", signatures.len()));
             }
 
-            if signatures.len() > 1 {
-                return err(&request.request_id, configuration.validator(), &format!("Only one signature is supported by SynchronizeBlockchain for now, received {}", signatures.len()));
+            let block_hash = blockchain.block_at_height(*height).ok_or(anyhow!("Block {} not found", height))?.hash.to_owned();
+
+            if block_hash != *blockchain_tip_after_transaction {
+                return err(&request.request_id, configuration.validator(), &format!("Blocks at height {} are different, synchronization needed. Incoming hash: {}, this blockchain hash: {}", height, blockchain_tip_after_transaction, block_hash));
             }
 
-            let signature = &signatures[0];
-            last.add_validator_signature(signature.into());
+            let signature = ValidatorSignature::from(&signatures[0]);
+            configuration.ensure_known_validator(&signature.validator_public_key)?;
+            blockchain.add_validator_signature(&block_hash, signature)?;
 
             success(&request.request_id, configuration.validator(), CommandResponse::SynchronizeBlockchainResponse{})
         },
 
         CommandRequest::PrintBlockchain => {
-            let blocks = blockchain.blocks.iter().enumerate().map(|(idx, block)| {
+            let blocks = blockchain.blocks().map(|block| {
                 let mut block_str = String::new();
-                block_str.push_str(&format!("{}. Block {}", idx + 1, block.hash));
-                block_str.push_str("\n  Input UTxOs:");
-                for (idx, input_utxo) in block.transaction.inputs().iter().enumerate() {
-                    block_str.push_str(&format!("\n    Input {}:", idx + 1));
-                    block_str.push_str(&format!("\n      Addr: {}", shorten_long_string(&input_utxo.address.0.0)));
-                    block_str.push_str(&format!("\n      Amount: {}", input_utxo.amount));
+                block_str.push_str(&format!("{}. Block {}", block.header.height, block.hash));
+                block_str.push_str(&format!("\n  Previous hash: {}", block.header.prev_hash));
+                block_str.push_str(&format!("\n  Timestamp: {}", block.header.timestamp));
+                block_str.push_str(&format!("\n  Proposer: {}", shorten_long_string(&block.header.proposer.0.0)));
+                block_str.push_str(&format!("\n  Transactions root: {}", block.header.transactions_root));
+                for (idx, transaction) in block.transactions.iter().enumerate() {
+                    block_str.push_str(&format!("\n  Transaction {}: {}", idx + 1, transaction.id()));
+                    block_str.push_str("\n    Input UTxOs:");
+                    for (idx, outpoint) in transaction.inputs().iter().enumerate() {
+                        block_str.push_str(&format!("\n      Input {}: {}", idx + 1, outpoint));
+                    }
+                    block_str.push_str("\n    Output UTxOs:");
+                    for (idx, output_utxo) in transaction.outputs().iter().enumerate() {
+                        block_str.push_str(&format!("\n      Output {}:", idx + 1));
+                        block_str.push_str(&format!("\n        Addr: {}", shorten_long_string(&output_utxo.address.0.0)));
+                        block_str.push_str(&format!("\n        Amount: {}", output_utxo.amount));
+                        if let Some(asset) = &output_utxo.asset {
+                            block_str.push_str(&format!("\n        Asset: {}", asset));
+                        }
+                        if let Some(lock) = output_utxo.lock {
+                            block_str.push_str(&format!("\n        Locked until: {}", lock));
+                        }
+                    }
+                    if let Some(issuance) = &transaction.balanced_transaction.issuance {
+                        block_str.push_str(&format!("\n    Issues {} of asset {} ({}) by {}", issuance.amount, issuance.name, issuance.asset_id(), shorten_long_string(&issuance.issuer.0.0)));
+                    }
+                    if let (Some(token), Some(mint)) = (transaction.balanced_transaction.minted_token(), &transaction.balanced_transaction.mint) {
+                        block_str.push_str(&format!("\n    Mints token {} with metadata {}", token, mint.metadata));
+                    }
+                    for signature in &transaction.signatures {
+                        block_str.push_str(&format!("\n    Signature of {}: {}", shorten_long_string(&signature.address.0.0), shorten_long_string(&signature.signature.0.0)));
+                    }
                 }
-                block_str.push_str("\n  Output UTxOs:");
-                for (idx, output_utxo) in block.transaction.outputs().iter().enumerate() {
-                    block_str.push_str(&format!("\n    Output {}:", idx + 1));
-                    block_str.push_str(&format!("\n      Addr: {}", shorten_long_string(&output_utxo.address.0.0)));
-                    block_str.push_str(&format!("\n      Amount: {}", output_utxo.amount));
-                }
-                block_str.push_str(&format!("\n  Transaction signature: {}", shorten_long_string(&block.transaction.signature.0.0)));
                 block_str.push_str(&format!("\n  Confirmations (total {}):", block.validator_signatures().len()));
                 for (idx, signature) in block.validator_signatures().iter().enumerate() {
                     block_str.push_str(&format!("\n    Confirmation {}:", idx + 1));
@@ -223,47 +269,45 @@ pub fn handle_request(
             success(&request.request_id, configuration.validator(), CommandResponse::PrintBlockchainResponse { blocks })
         },
 
-        CommandRequest::RequestSynchronization { blockchain_tip } => {
-            debug!("Request synchronization received for tip {}", blockchain_tip);
-            let block_index = blockchain.index_of_block(blockchain_tip);
-            debug!("Found block at {}", block_index);
+        CommandRequest::AuditBlockchain => {
+            let (corrupted_height, reason) = match blockchain.audit() {
+                Ok(_) => (None, None),
+                Err(failure) => {
+                    error!("{}", failure);
+                    (Some(failure.height), Some(failure.reason))
+                },
+            };
+            success(&request.request_id, configuration.validator(), CommandResponse::AuditBlockchainResponse { blocks: blockchain.len() as u64, corrupted_height, reason })
+        },
 
-            if blockchain.blockchain_hash()? == *blockchain_tip {
-                return err(&request.request_id, configuration.validator(), "Fully synchronized");
-            }
+        CommandRequest::RequestSynchronization { blockchain_tip, height } => {
+            debug!("Request synchronization received for tip {} at height {}", blockchain_tip, height);
 
-            if block_index >= 0 || blockchain.initial_utxo.hash_str() == *blockchain_tip {
-                let next_block = &blockchain.blocks[(block_index + 1) as usize];
-                let next_hash = next_block.hash.to_owned();
-                let previous_hash = if block_index == -1 {
-                    blockchain.initial_utxo.hash_str()
-                } else {
-                    let hash = &blockchain.blocks[block_index as usize].hash;
-                    hash.to_owned()
-                };
-
-                let response = CommandResponse::RequestSynchronizationResponse {
-                    previous_hash, 
-                    next_hash, 
-                    transaction_cbor: Cbor::try_from(&next_block.transaction)?.0, 
-                    signatures: next_block.validator_signatures().iter().map(|v| ValidatorWithSignature::from(v)).collect() 
-                };
-
-                return success(&request.request_id, configuration.validator(), response);
-            } else {
-                let err_msg = format!("Impossible to synchronize, no common ancestor for hash {}", blockchain_tip);
+            if blockchain.height_of_block(blockchain_tip) != Some(*height) {
+                let err_msg = format!("Impossible to synchronize, no common ancestor for hash {} at height {}", blockchain_tip, height);
                 return err(&request.request_id, configuration.validator(), &err_msg);
             }
+
+            if blockchain.height() == *height {
+                return err(&request.request_id, configuration.validator(), "Fully synchronized");
+            }
+
+            let next_block = blockchain.block_at_height(height + 1).ok_or(anyhow!("Block {} not found", height + 1))?;
+            let response = CommandResponse::RequestSynchronizationResponse {
+                height: next_block.header.height,
+                previous_hash: blockchain_tip.to_owned(),
+                next_hash: next_block.hash.to_owned(),
+                block_cbor: Cbor::try_from(next_block)?.0,
+            };
+
+            success(&request.request_id, configuration.validator(), response)
         },
 
         CommandRequest::AddValidatorSignature { hash, validator_signature } => {
             print!("Received AddValidatorSignature request");
-            let block_index = blockchain.index_of_block(hash);
-
-            if block_index >= 0 {
-                let block = &mut blockchain.blocks[block_index as usize];
-                block.add_validator_signature(ValidatorSignature::from(validator_signature));
-            }
+            let validator_signature = ValidatorSignature::from(validator_signature);
+            configuration.ensure_known_validator(&validator_signature.validator_public_key)?;
+            blockchain.add_validator_signature(hash, validator_signature)?;
             debug!("Added validator signature for {} block", hash);
 
             no_response(&request.request_id, configuration.validator())
@@ -273,12 +317,78 @@ pub fn handle_request(
             let buf = String::new();
 
             _success(CommandResponse::PrintValidatorsResponse(_PrintValidatorsResponse { 
-                validators: configuration.validators.iter().map(|v| Validator::from(v)).collect() 
+                validators: configuration.validators.iter().map(Validator::from).collect() 
             }))
         },
     }
 }
 
+/**
+ * Commits transactions into a single block and asks other validators to validate it
+ */
+fn commit_transactions(
+    request: &Request,
+    blockchain: &mut BlockChain,
+    configuration: &Configuration,
+    signed_transactions_cbor: &[String],
+) -> Result<(Response, Vec<(ValidatorReference, Request)>)> {
+    let blockchain_previous_tip = blockchain.blockchain_hash();
+    let signed_transactions = signed_transactions_cbor.iter()
+        .map(|cbor| SignedBalancedTransaction::try_from(&Cbor::new(cbor)))
+        .collect::<Result<Vec<_>>>()?;
+
+    // resubmitted transactions are answered with the block they are already committed in
+    if let Some(block) = blockchain.committed_block(&signed_transactions) {
+        debug!("Transactions are already committed in block {}", block.hash);
+        return ok(commit_transactions_response(request, configuration, &block.hash, &signed_transactions));
+    }
+
+    for signed_transaction in &signed_transactions {
+        configuration.ensure_min_fee(signed_transaction, blockchain.fee(signed_transaction)?)?;
+    }
+    let block = blockchain.commit_transactions(&signed_transactions, &configuration.validator_private_key)?;
+    let block_cbor = Cbor::try_from(&block)?.0;
+
+    let mut requests = Vec::new();
+
+    for validator in &configuration.validators {
+        let request = CommandRequest::RequestTransactionValidation {
+            blockchain_previous_tip: blockchain_previous_tip.to_owned(),
+            blockchain_new_tip: block.hash.to_owned(),
+            block_cbor: block_cbor.to_owned(),
+        }.to_request(&configuration.validator());
+
+        requests.push((validator.clone(), request));
+    }
+
+    trace!("{}", serde_json::to_string_pretty(&block)?);
+
+    ok_with_requests(commit_transactions_response(request, configuration, &block.hash, &signed_transactions), requests)
+}
+
+fn balance_transaction_response(request: &Request, configuration: &Configuration, balanced_transaction: &BalancedTransaction) -> Result<(Response, Vec<(ValidatorReference, Request)>)> {
+    let cbor_bytes = balanced_transaction.to_cbor()?;
+    let cbor = hex::encode(&cbor_bytes);
+    let body = serde_json::to_string_pretty(balanced_transaction)?;
+
+    let txid = balanced_transaction.id().0;
+
+    success(&request.request_id, configuration.validator(), CommandResponse::BalanceTransactionResponse { request_id: request.request_id.clone(), body, cbor, txid })
+}
+
+fn commit_transactions_response(request: &Request, configuration: &Configuration, block_hash: &str, transactions: &[SignedBalancedTransaction]) -> Response {
+    Response {
+        orig_request_id: request.request_id.to_owned(),
+        replier: configuration.validator(),
+        body: ResponseBody::Success (
+            CommandResponse::CommitTransactionResponse {
+                blockchain_hash: block_hash.to_owned(),
+                txids: transactions.iter().map(|transaction| transaction.id().0).collect(),
+            },
+        ),
+    }
+}
+
 fn ok(response: Response) -> Result<(Response, Vec<(ValidatorReference, Request)>)> {
     Ok((response, Vec::new()))
 }
@@ -0,0 +1,27 @@
+#[cfg(test)]
+mod tests {
+    use protocol::request::{CommandRequest, ResponseBody};
+
+    use crate::{blockchain::{blockchain::BlockChain, genesis::{Genesis, GenesisAllocation}}, configuration::Configuration, encryption::generate_rsa_keypair_custom, request_handlers::handle_request};
+
+    #[test]
+    fn synchronization_without_signatures_is_rejected() {
+        let (validator_private_key, validator_public_key) = &generate_rsa_keypair_custom().unwrap();
+        let mut blockchain = BlockChain::new(validator_public_key, Genesis::new("test", vec![GenesisAllocation::new(validator_public_key, 10)], Vec::new()));
+        let mut configuration = Configuration::new("127.0.0.1", 9065, validator_private_key);
+
+        let request = CommandRequest::SynchronizeBlockchain {
+            signatures: Vec::new(),
+            height: 1,
+            blockchain_tip_before_transaction: blockchain.blockchain_hash(),
+            blockchain_tip_after_transaction: blockchain.blockchain_hash(),
+        }.to_client_request();
+        let (response, requests) = handle_request(&request, &mut blockchain, &mut configuration).unwrap();
+
+        match response.body {
+            ResponseBody::Error { msg } => assert!(msg.contains("received 0"), "Error is wrong: {}", msg),
+            body => panic!("Request must be rejected, got {:?}", body),
+        }
+        assert!(requests.is_empty(), "Nothing must be sent to other validators");
+    }
+}
@@ -2,29 +2,34 @@ use anyhow::{Result, anyhow};
 use log::debug;
 use protocol::{request::CommandResponse, request::{Request, Response, CommandRequest, ValidatorWithSignature, Validator, ResponseBody}};
 
-use crate::{blockchain::{blockchain::BlockChain, signed_balanced_transaction::SignedBalancedTransaction, cbor::Cbor}, model::{PublicKeyStr, Signature}, configuration::{ValidatorReference, ValidatorAddress, Configuration}};
+use crate::{blockchain::{blockchain::BlockChain, block::Block, cbor::Cbor}, model::{PublicKeyStr, Signature}, configuration::{ValidatorReference, ValidatorAddress, Configuration}};
 
 use super::blockchain::validator_signature::ValidatorSignature;
 
 pub fn handle_response(blockchain: &mut BlockChain, configuration: &mut Configuration, request_id: &str, response: &Response) -> Result<Vec<(ValidatorReference, Request)>> {
     match response {
-        Response { orig_request_id, replier, body: ResponseBody::Success(response) } => handle_command(blockchain, configuration, request_id, &replier, response),
+        Response { orig_request_id, replier, body: ResponseBody::Success(response) } => handle_command(blockchain, configuration, request_id, replier, response),
         Response { orig_request_id, replier, body: ResponseBody::Error { msg } } => Err(anyhow!(msg.to_owned())),
     }
 }
 
 fn handle_command(blockchain: &mut BlockChain, configuration: &mut Configuration, request_id: &str, replier: &Validator, response: &CommandResponse) -> Result<Vec<(ValidatorReference, Request)>> {
     match response {
-        CommandResponse::OnBoardValidatorResponse { on_boarding_validator, validators, blockchain_tip } => {
+        CommandResponse::OnBoardValidatorResponse { chain_id, on_boarding_validator, validators, blockchain_tip } => {
+            if *chain_id != blockchain.genesis.chain_id {
+                return Err(anyhow!("Validator {} belongs to chain {}, but this is chain {}", on_boarding_validator.address, chain_id, blockchain.genesis.chain_id));
+            }
+
             let new_validators: Vec<_> = validators.iter().map(|v| ValidatorReference { pk: PublicKeyStr::from_str(&v.public_key), address: ValidatorAddress(v.address.to_owned())}).collect();
             configuration.add_validators(&new_validators);
             debug!("Validators added: {:?}", configuration.validators.iter().map(|validator| &validator.address).collect::<Vec<&ValidatorAddress>>());
 
-            let this_blockchain_tip = blockchain.blockchain_hash()?;
+            let this_blockchain_tip = blockchain.blockchain_hash();
             if this_blockchain_tip != *blockchain_tip {
                 let validator = ValidatorReference::from(on_boarding_validator);
                 let command = CommandRequest::RequestSynchronization {
                     blockchain_tip: this_blockchain_tip,
+                    height: blockchain.height(),
                 };
                 return ok_with_requests(vec![(on_boarding_validator.into(), command.to_request(&configuration.validator()))]);
             }
@@ -35,17 +40,16 @@ fn handle_command(blockchain: &mut BlockChain, configuration: &mut Configuration
         CommandResponse::RequestTransactionValidationResponse { 
             old_blockchain_tip, new_blockchain_tip, 
             validator_public_key, 
-            transaction_cbor, 
+            height,
             validator_signature: _validator_signature
         } => {
-            let last = blockchain.blocks.last_mut().unwrap();
-            let validator_signature = ValidatorSignature::new(&PublicKeyStr::from_str(&validator_public_key), &Signature::from_string(&_validator_signature));
+            let validator_signature = ValidatorSignature::new(&PublicKeyStr::from_str(validator_public_key), &Signature::from_string(_validator_signature));
             let validator_signature_json = serde_json::to_string_pretty(&validator_signature)?;
-            last.add_validator_signature(validator_signature);
-            debug!("New validation added (total {}) {}", last.validator_signatures().len(), validator_signature_json);
-
-            let prev_block = "not needed atm"; // &blockchain.blocks[blockchain.blocks.len() - 2];
-            let current_block = &blockchain.blocks[blockchain.blocks.len() - 1];
+            configuration.ensure_known_validator(&validator_signature.validator_public_key)?;
+            match blockchain.add_validator_signature(new_blockchain_tip, validator_signature)? {
+                Some(block) => debug!("New validation added (total {}) {}", block.validator_signatures().len(), validator_signature_json),
+                None => return Err(anyhow!("Validated block {} at height {} not found", new_blockchain_tip, height)),
+            }
 
             let requests = configuration.validators.iter().flat_map(|ValidatorReference { pk: validator_pub_key, address: validator_addr } | {
                 if *validator_pub_key != configuration.validator_public_key {
@@ -54,9 +58,9 @@ fn handle_command(blockchain: &mut BlockChain, configuration: &mut Configuration
                             signatures: vec![ValidatorWithSignature { 
                                 validator: Validator { address: validator_address.0.to_owned(), public_key: validator_public_key.to_owned() }, signature: _validator_signature.to_owned() 
                             }],
-                            transaction_cbor: transaction_cbor.to_owned(),
-                            blockchain_tip_before_transaction: prev_block.to_owned(),
-                            blockchain_tip_after_transaction: current_block.hash.to_owned(),
+                            height: *height,
+                            blockchain_tip_before_transaction: old_blockchain_tip.to_owned(),
+                            blockchain_tip_after_transaction: new_blockchain_tip.to_owned(),
                         };
                         debug!("Synchronisation request will be sent to {}", validator_address.0);
                         let request = command.to_request(&configuration.validator());
@@ -72,25 +76,29 @@ fn handle_command(blockchain: &mut BlockChain, configuration: &mut Configuration
 
             ok_with_requests(requests)
         },
-        CommandResponse::RequestSynchronizationResponse { previous_hash, next_hash, transaction_cbor, signatures } => {
-            debug!("Processing RequestSynchronizationResponse. Base hash {}, expected hash {}", previous_hash, next_hash);
-            let current_blockchain_tip = blockchain.blockchain_hash()?;
+        CommandResponse::RequestSynchronizationResponse { height, previous_hash, next_hash, block_cbor } => {
+            debug!("Processing RequestSynchronizationResponse. Base hash {}, expected hash {} at height {}", previous_hash, next_hash, height);
+            let current_blockchain_tip = blockchain.blockchain_hash();
             if current_blockchain_tip != *previous_hash {
                 debug!("RequestSynchronizationResponse is impossible because base hash from the requester {} does not match to base hash of the receiver {}", previous_hash, current_blockchain_tip);
                 return ok();
             }
 
-            let signed_transaction = SignedBalancedTransaction::try_from(&Cbor::new(&transaction_cbor))?;
-            let block = signed_transaction.commit(blockchain, &configuration.validator_private_key)?;
-            let validator_signature = block.validator_signatures().first().ok_or(anyhow!("Transaction wasn't signed by validator"))?;
-
-            debug!("Transaction applied, new block hash is {}", block.hash);
-
-            if block.hash.to_owned() != *next_hash {
-                debug!("RequestSynchronizationResponse failed because resulting hash {} does not match to expected hash {}", block.hash, next_hash);
+            let block = Block::try_from(&Cbor::new(block_cbor))?;
+            if block.hash != *next_hash {
+                debug!("RequestSynchronizationResponse failed because received block hash {} does not match to expected hash {}", block.hash, next_hash);
                 return ok();
             }
 
+            for signature in block.validator_signatures() {
+                configuration.ensure_known_validator(&signature.validator_public_key)?;
+            }
+
+            let block = blockchain.append_block(block, &configuration.validator_private_key)?;
+            let validator_signature = block.signature_of(&configuration.validator_public_key).ok_or(anyhow!("Block wasn't signed by validator"))?;
+
+            debug!("Block {} applied, new block hash is {}", block.header.height, block.hash);
+
             let synchronisaction_command = CommandRequest::AddValidatorSignature { 
                 hash: block.hash.to_owned(),
                 validator_signature: ValidatorWithSignature::from(validator_signature), 
@@ -98,6 +106,7 @@ fn handle_command(blockchain: &mut BlockChain, configuration: &mut Configuration
 
             let command = CommandRequest::RequestSynchronization {
                 blockchain_tip: block.hash.to_owned(),
+                height: block.header.height,
             };
             let request = command.to_request(&configuration.validator());
 
@@ -1,3 +1,5 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
 pub fn shorten_long_string(str: &str) -> String {
     let size = 20;
     let mut res = String::new();
@@ -5,4 +7,11 @@ pub fn shorten_long_string(str: &str) -> String {
     res += "....";
     res += &str[str.len() - size..str.len()];
     res.to_string()
-}
\ No newline at end of file
+}
+
+/**
+ * Milliseconds since unix epoch
+ */
+pub fn now_millis() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|duration| duration.as_millis() as u64).unwrap_or(0)
+}
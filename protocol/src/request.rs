@@ -27,23 +27,69 @@ pub enum CommandRequest {
     PingCommand { msg: String },
     GenerateWallet,
     PrintBalances,
+    GetBalance {
+        address: String,
+    },
     PrintValidators,
     BalanceTransaction {
+        // funds are taken from any of the addresses, every address spent from has to sign the transaction
+        from: Vec<String>,
+        // recipients with amounts, every recipient gets its own output
+        to: Vec<(String, u64)>,
+        // fee per input and output, the node may require a higher one
+        fee_rate: u64,
+        // strategy choosing the inputs: largest-first, smallest-first, oldest-first or branch-and-bound
+        coin_selection: String,
+        // outputs paid to the recipients can't be spent before the block at this height
+        lock_height: Option<u64>,
+        // or before a block with this timestamp, milliseconds since unix epoch
+        lock_timestamp: Option<u64>,
+        // id of the asset paid to the recipients, the native coin if missing
+        asset: Option<String>,
+        // name of a new asset issued by the first sender, the recipients get the whole issued amount
+        issue: Option<String>,
+    },
+    // balanced transactions minting and transferring tokens, they are signed and committed like any other
+    MintToken {
         from: String,
         to: String,
-        amount: u64,
+        // hex encoded bytes describing the token
+        metadata: String,
+        fee_rate: u64,
+    },
+    TransferToken {
+        from: String,
+        to: String,
+        token_id: String,
+        fee_rate: u64,
+    },
+    GetTokenOwner {
+        token_id: String,
+    },
+    GetSupply,
+    // owners of the outputs spent by the balanced transaction, so the client knows which keys sign it
+    GetInputOwners {
+        cbor: String,
     },
     CommitTransaction {
         signed_transaction_cbor: String,
     },
+    // all transactions are committed in a single block, in the given order
+    CommitTransactions {
+        signed_transactions_cbor: Vec<String>,
+    },
     PrintBlockchain,
+    AuditBlockchain,
     OnBoardValidator {
         public_key: String,
         return_address: String,
+        // validators of different chains never talk to each other
+        chain_id: String,
     },
     SynchronizeBlockchain {
         signatures: Vec<ValidatorWithSignature>,
-        transaction_cbor: String,
+        // height of the block the signatures belong to
+        height: u64,
         blockchain_tip_before_transaction: String,
         blockchain_tip_after_transaction: String,
     },
@@ -52,12 +98,13 @@ pub enum CommandRequest {
         blockchain_previous_tip: String,
         // blockchain hash after transaction was committed
         blockchain_new_tip: String,
-        transaction_cbor: String,
-        validator_signature: ValidatorWithSignature,
-        validator: Validator
+        // block created by the sender, signed by the sender
+        block_cbor: String,
     },
     RequestSynchronization {
-        blockchain_tip: String
+        blockchain_tip: String,
+        // height of the tip, 0 if the blockchain has no blocks
+        height: u64,
     },
     AddValidatorSignature {
         hash: String,
@@ -75,21 +122,65 @@ pub enum CommandResponse {
         public_key: String,
     },
     PrintBalancesResponse {
-        balances: Vec<(String, u64)>
+        balances: Vec<(String, u64)>,
+        // amounts which can't be spent yet, only addresses owning locked outputs are listed
+        locked: Vec<(String, u64)>,
+        // address, asset id and amount of every asset other than the native coin
+        assets: Vec<(String, String, u64)>,
+        locked_assets: Vec<(String, String, u64)>,
+    },
+    GetBalanceResponse {
+        address: String,
+        // spendable amount and number of spendable utxos
+        amount: u64,
+        utxos: u64,
+        locked: u64,
+        // asset ids with amounts of every asset other than the native coin
+        assets: Vec<(String, u64)>,
+        locked_assets: Vec<(String, u64)>,
     },
     PrintValidatorsResponse(_PrintValidatorsResponse),
     BalanceTransactionResponse {
         request_id: String,
         body: String,
         cbor: String,
+        // id of the transaction, it doesn't change when the transaction is signed
+        txid: String,
+    },
+    GetSupplyResponse {
+        // native coins held by spendable outputs and by outputs which can't be spent yet
+        circulating: u64,
+        locked: u64,
+        // created by the genesis allocations and the block subsidies so far
+        total_issued: u64,
+    },
+    GetTokenOwnerResponse {
+        token_id: String,
+        owner: String,
+        // output holding the token
+        outpoint: String,
+        metadata: String,
+    },
+    GetInputOwnersResponse {
+        // in the order of the inputs
+        owners: Vec<String>,
     },
     CommitTransactionResponse {
         blockchain_hash: String,
+        // ids of the committed transactions, in the order they were given
+        txids: Vec<String>,
     },
     PrintBlockchainResponse {
         blocks: Vec<String>
     },
+    AuditBlockchainResponse {
+        blocks: u64,
+        // height of the first corrupted block and the reason, none if the blockchain is intact
+        corrupted_height: Option<u64>,
+        reason: Option<String>,
+    },
     OnBoardValidatorResponse {
+        chain_id: String,
         on_boarding_validator: Validator,
         validators: Vec<Validator>,
         blockchain_tip: String
@@ -101,14 +192,17 @@ pub enum CommandResponse {
         // blockchain hash after the transaction was applied
         new_blockchain_tip: String,
         validator_public_key: String,
-        transaction_cbor: String,
+        // height of the validated block
+        height: u64,
         validator_signature: String
     },
     RequestSynchronizationResponse {
+        // height of the next block
+        height: u64,
         previous_hash: String,
         next_hash: String,
-        transaction_cbor: String,
-        signatures: Vec<ValidatorWithSignature>,
+        // next block with all signatures collected so far
+        block_cbor: String,
     },
     Nothing
 }
@@ -139,11 +233,35 @@ impl CommandRequest {
         }
     }
 
-    pub fn new_balance_transaction(from: &str, to: &str, amount: u64) -> Self {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new_balance_transaction(from: &[&str], to: &[(&str, u64)], fee_rate: u64, coin_selection: &str, lock_height: Option<u64>, lock_timestamp: Option<u64>, asset: Option<&str>, issue: Option<&str>) -> Self {
         Self::BalanceTransaction {
-            from: from.to_string(),
-            to: to.to_string(),
-            amount,
+            from: from.iter().map(|address| address.to_string()).collect(),
+            to: to.iter().map(|(address, amount)| (address.to_string(), *amount)).collect(),
+            fee_rate,
+            coin_selection: coin_selection.to_owned(),
+            lock_height,
+            lock_timestamp,
+            asset: asset.map(str::to_owned),
+            issue: issue.map(str::to_owned),
+        }
+    }
+
+    pub fn new_mint_token(from: &str, to: &str, metadata: &str, fee_rate: u64) -> Self {
+        Self::MintToken {
+            from: from.to_owned(),
+            to: to.to_owned(),
+            metadata: metadata.to_owned(),
+            fee_rate,
+        }
+    }
+
+    pub fn new_transfer_token(from: &str, to: &str, token_id: &str, fee_rate: u64) -> Self {
+        Self::TransferToken {
+            from: from.to_owned(),
+            to: to.to_owned(),
+            token_id: token_id.to_owned(),
+            fee_rate,
         }
     }
 
@@ -153,10 +271,17 @@ impl CommandRequest {
         }
     }
 
-    pub fn new_on_board_command(return_address: &str, public_key: &str) -> CommandRequest {
+    pub fn new_commit_transactions(signed_transactions_cbor: &[String]) -> Self {
+        Self::CommitTransactions {
+            signed_transactions_cbor: signed_transactions_cbor.to_vec(),
+        }
+    }
+
+    pub fn new_on_board_command(return_address: &str, public_key: &str, chain_id: &str) -> CommandRequest {
         CommandRequest::OnBoardValidator {
             return_address: return_address.to_owned(),
             public_key: public_key.to_owned(),
+            chain_id: chain_id.to_owned(),
         }
     }
 
@@ -1,3 +1,3 @@
 RUST_LOG=debug \
 RUST_BACKTRACE=1 \
-RUST_BACKTRACE=1 target/debug/node --port 9068 --remote-validator 0.0.0.0:9065 --private-key "$(<test-data/validator-2_private_key)" --public-key "$(<test-data/validator-2_public_key)"
\ No newline at end of file
+RUST_BACKTRACE=1 target/debug/node --port 9068 --remote-validator 0.0.0.0:9065 --private-key "$(<test-data/validator-2_private_key)" --public-key "$(<test-data/validator-2_public_key)" --genesis test-data/genesis.json --data-dir data/validator-2
\ No newline at end of file